@@ -0,0 +1,104 @@
+use anyhow::anyhow;
+use clap::Args;
+use git_gen_core::message::ticket::{DEFAULT_FOOTER_TEMPLATE, DEFAULT_TICKET_PATTERNS};
+use git_gen_core::{
+    BranchService, BranchServiceImpl, GitRepository, LlmGenerater, TicketPlacement, TicketReference,
+};
+use git_gen_git::GitRepositoryImpl;
+
+use crate::commands::commit::resolve_rules;
+use crate::config::AppConfig;
+use crate::journal::{self, MessageStatus};
+use crate::ledger::UsageTracker;
+use crate::llm_provider::LlmProvider;
+
+#[derive(Args)]
+pub struct BranchArgs {
+    /// Task description; the staged changes are used if omitted
+    #[arg(value_name = "DESCRIPTION")]
+    pub description: Vec<String>,
+
+    /// Ticket reference for the `{ticket}` placeholder
+    #[arg(short, long)]
+    pub ticket: Option<String>,
+
+    /// Name pattern, overriding `[branch] pattern`
+    #[arg(short, long)]
+    pub pattern: Option<String>,
+
+    /// Create the branch and switch to it
+    #[arg(short, long)]
+    pub create: bool,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+}
+
+pub async fn run(args: BranchArgs, config: AppConfig, usage: &UsageTracker) -> anyhow::Result<()> {
+    let description = args.description.join(" ");
+    let description = Some(description.trim()).filter(|d| !d.is_empty());
+
+    let provider = LlmProvider::resolve(args.llm.clone(), &config)?;
+    let llm_generater: Box<dyn LlmGenerater> =
+        provider.create_llm_generater_from_config(&config, usage)?;
+    let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
+
+    let root = git_repository.root_dir().await?;
+    let style = config.message_style(None)?;
+    let rules = resolve_rules(&config, &root, &style)?;
+
+    // Without a [ticket] section, still pick up Jira keys and issue numbers
+    let ticket = match &config.ticket {
+        Some(_) => config.ticket_reference()?,
+        None => Some(TicketReference::new(
+            &DEFAULT_TICKET_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>(),
+            TicketPlacement::Footer,
+            DEFAULT_FOOTER_TEMPLATE.to_string(),
+        )?),
+    };
+
+    let branch_service = BranchServiceImpl::new(llm_generater, git_repository)
+        .with_pattern(
+            args.pattern
+                .clone()
+                .unwrap_or_else(|| config.branch_pattern()),
+        )
+        .with_rules(rules)
+        .with_scope_mappings(config.scope_mappings(&root)?)
+        .with_ticket(ticket);
+
+    let suggestion = match branch_service
+        .suggest(description, args.ticket.as_deref())
+        .await
+    {
+        Ok(suggestion) => suggestion,
+        Err(e) => return Err(anyhow!("Failed to generate branch name: {}", e)),
+    };
+    println!("{}", suggestion.name);
+
+    let history_id = journal::record_generated(
+        "branch",
+        provider.name(),
+        provider.model(&config).unwrap_or_default(),
+        &suggestion.name,
+    )
+    .await;
+
+    if args.create {
+        match branch_service.create_branch(&suggestion.name).await {
+            Ok(_) => {
+                journal::update_status(history_id, MessageStatus::Accepted);
+                println!("✓ Switched to a new branch '{}'", suggestion.name)
+            }
+            Err(e) => {
+                journal::update_status(history_id, MessageStatus::Failed);
+                return Err(anyhow!("Failed to create branch: {}", e));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use anyhow::anyhow;
+use clap::Args;
+use git_gen_core::{BumpService, BumpServiceImpl, GitRepository, LlmGenerater};
+use git_gen_git::GitRepositoryImpl;
+use std::io::{self, Write};
+
+use crate::config::AppConfig;
+use crate::ledger::UsageTracker;
+use crate::llm_provider::LlmProvider;
+
+#[derive(Args)]
+pub struct BumpArgs {
+    /// Create an annotated tag for the recommended version
+    #[arg(short, long)]
+    pub tag: bool,
+
+    /// Tag name to use instead of the recommended version
+    #[arg(long, value_name = "NAME", requires = "tag")]
+    pub name: Option<String>,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+}
+
+pub async fn run(args: BumpArgs, config: AppConfig, usage: &UsageTracker) -> anyhow::Result<()> {
+    let provider = LlmProvider::resolve(args.llm.clone(), &config)?;
+    let llm_generater: Box<dyn LlmGenerater> =
+        provider.create_llm_generater_from_config(&config, usage)?;
+    let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
+
+    let bump_service = BumpServiceImpl::new(llm_generater, git_repository);
+    let recommendation = bump_service.recommend().await?;
+
+    println!(
+        "Current version: {}",
+        recommendation.current.as_deref().unwrap_or("none")
+    );
+    println!(
+        "Recommended bump: {} → {}",
+        recommendation.bump.as_str(),
+        recommendation.next
+    );
+    if !recommendation.reasons.is_empty() {
+        println!();
+        println!("Reasons:");
+        for reason in &recommendation.reasons {
+            println!("  [{}] {}", reason.bump.as_str(), reason.description);
+        }
+    }
+
+    if !args.tag {
+        println!();
+        println!("ℹ Use --tag to create the release tag");
+        return Ok(());
+    }
+
+    let name = args.name.unwrap_or_else(|| recommendation.next.clone());
+    let message = match bump_service.tag_message(&recommendation).await {
+        Ok(message) => message,
+        Err(e) => return Err(anyhow!("Failed to generate tag message: {}", e)),
+    };
+    println!();
+    println!("{}", message);
+    println!();
+
+    print!("Create tag {}? [Y/n]: ", name);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+
+    if input.is_empty() || input == "y" || input == "yes" {
+        match bump_service.create_tag(&name, &message).await {
+            Ok(_) => {
+                println!("✓ Created tag {}", name);
+                Ok(())
+            }
+            Err(e) => Err(anyhow!("Failed to create tag: {}", e)),
+        }
+    } else {
+        println!("ℹ Tag cancelled");
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+
+use crate::config::AppConfig;
+use crate::response_cache;
+
+#[derive(Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Remove all cached responses
+    Clear,
+}
+
+pub async fn run(args: CacheArgs, _config: AppConfig) -> anyhow::Result<()> {
+    let dir = AppConfig::get_cache_dir()
+        .ok_or_else(|| anyhow!("Failed to locate the cache: no home directory"))?;
+
+    match args.command {
+        CacheCommand::Clear => {
+            let count =
+                response_cache::clear(&dir).map_err(|e| anyhow!("Failed to clear cache: {}", e))?;
+            println!("✓ Removed {} cached response(s)", count);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use anyhow::{Context, anyhow};
+use clap::Args;
+use git_gen_core::changelog::{UNRELEASED, update_changelog};
+use git_gen_core::{ChangelogService, ChangelogServiceImpl, GitRepository, LlmGenerater};
+use git_gen_git::GitRepositoryImpl;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::AppConfig;
+use crate::ledger::{UsageTracker, now, utc_date};
+use crate::llm_provider::LlmProvider;
+
+#[derive(Args)]
+pub struct ChangelogArgs {
+    /// Commits to describe, e.g. `v1.0.0..v1.1.0`; a single revision means `<rev>..HEAD`
+    pub range: String,
+
+    /// Name of the release section
+    #[arg(short, long, default_value = UNRELEASED)]
+    pub release: String,
+
+    /// Changelog to update, relative to the repository root
+    #[arg(short, long, value_name = "FILE", default_value = "CHANGELOG.md")]
+    pub file: PathBuf,
+
+    /// Release date for the section heading (YYYY-MM-DD), defaults to today in UTC
+    #[arg(long, value_name = "DATE")]
+    pub date: Option<String>,
+
+    /// Print the section instead of updating the changelog
+    #[arg(long)]
+    pub print: bool,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+}
+
+pub async fn run(
+    args: ChangelogArgs,
+    config: AppConfig,
+    usage: &UsageTracker,
+) -> anyhow::Result<()> {
+    let range = if args.range.contains("..") {
+        args.range.clone()
+    } else {
+        format!("{}..HEAD", args.range)
+    };
+
+    let provider = LlmProvider::resolve(args.llm.clone(), &config)?;
+    let llm_generater: Box<dyn LlmGenerater> =
+        provider.create_llm_generater_from_config(&config, usage)?;
+    let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
+    let path = git_repository.root_dir().await?.join(&args.file);
+
+    let date = args.date.clone().unwrap_or_else(|| utc_date(now()));
+    let changelog_service =
+        ChangelogServiceImpl::new(llm_generater, git_repository).with_date(Some(date));
+    let section = match changelog_service.generate(&range, &args.release).await {
+        Ok(section) => section,
+        Err(e) => return Err(anyhow!("Failed to generate changelog: {}", e)),
+    };
+
+    if args.print {
+        println!("{}", section);
+        return Ok(());
+    }
+
+    let existing = if path.exists() {
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    fs::write(&path, update_changelog(&existing, &args.release, &section))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!("{}", section);
+    println!("✓ Updated {}", path.display());
+    Ok(())
+}
@@ -1,48 +1,238 @@
 use anyhow::anyhow;
-use git_gen_core::{CommitService, CommitServiceImpl, GitRepository, LlmGenerater};
+use clap::Args;
+use git_gen_core::{
+    CommitOptions, CommitRules, CommitService, CommitServiceImpl, Footer, GitRepository,
+    HeaderFormat, LlmGenerater, MessageStyle, Severity,
+};
 use git_gen_git::GitRepositoryImpl;
 use std::io::{self, Write};
+use std::path::Path;
 
-use crate::config::AppConfig;
+use crate::commands::review::{print_review, review_staged};
+use crate::commitlint::CommitlintConfig;
+use crate::config::{AppConfig, parse_trailer};
+use crate::journal::{self, MessageStatus};
+use crate::ledger::UsageTracker;
 use crate::llm_provider::LlmProvider;
+use crate::response_cache::CachedLlmGenerater;
 
-pub async fn run(
-    provider: Option<LlmProvider>,
-    apply: bool,
-    config: AppConfig,
-) -> anyhow::Result<()> {
-    // Determine LLM provider
-    let provider = match provider {
-        Some(p) => {
-            // Validate that the specified provider is configured
-            let provider_name = match p {
-                LlmProvider::OpenAI => "openai",
-                LlmProvider::Gemini => "gemini",
-            };
-            if !config.has_provider(provider_name) {
-                return Err(anyhow!(
-                    "{} is not configured in config file or environment variables",
-                    provider_name
-                ));
-            }
-            p
+/// Review staged changes and decide whether the commit may proceed
+///
+/// Error findings require an explicit confirmation, defaulting to no.
+async fn review_gate(
+    llm: Option<LlmProvider>,
+    config: &AppConfig,
+    usage: &UsageTracker,
+) -> anyhow::Result<bool> {
+    let review = review_staged(llm, config, usage).await?;
+    println!();
+    print_review(&review);
+    println!();
+
+    let errors = review.count_at_least(Severity::Error);
+    if errors == 0 {
+        return Ok(true);
+    }
+
+    print!("Review found {} error(s). Commit anyway? [y/N]: ", errors);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
+
+/// Number of recent authors offered by the co-author picker
+const CO_AUTHOR_CANDIDATES: usize = 10;
+
+/// Trailers and `git commit` options shared by the commands that create commits
+#[derive(Args)]
+pub struct CommitFlags {
+    /// Add a Signed-off-by trailer for the committer
+    #[arg(short, long)]
+    pub signoff: bool,
+
+    /// Add a Co-authored-by trailer ("Name <email>"), can be repeated
+    #[arg(long = "co-author", value_name = "NAME <EMAIL>")]
+    pub co_authors: Vec<String>,
+
+    /// Pick co-authors interactively from recent commit authors
+    #[arg(long)]
+    pub pick_co_authors: bool,
+
+    /// Add an arbitrary trailer ("Token: value"), can be repeated
+    #[arg(long = "trailer", value_name = "TOKEN: VALUE")]
+    pub trailers: Vec<String>,
+
+    /// GPG/SSH-sign the commit, optionally with a specific key
+    #[arg(
+        short = 'S',
+        long = "gpg-sign",
+        value_name = "KEYID",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ""
+    )]
+    pub gpg_sign: Option<String>,
+
+    /// Do not sign the commit, even if commit.gpgsign is set
+    #[arg(long, conflicts_with = "gpg_sign")]
+    pub no_gpg_sign: bool,
+
+    /// Bypass the pre-commit and commit-msg hooks
+    #[arg(short = 'n', long)]
+    pub no_verify: bool,
+
+    /// Override the commit author ("Name <email>")
+    #[arg(long, value_name = "AUTHOR")]
+    pub author: Option<String>,
+
+    /// Override the author date
+    #[arg(long)]
+    pub date: Option<String>,
+
+    /// How to clean up the commit message (strip, whitespace, verbatim, scissors, default)
+    #[arg(long, value_name = "MODE")]
+    pub cleanup: Option<String>,
+}
+
+impl CommitFlags {
+    /// Build the options passed to `git commit`
+    pub fn commit_options(&self) -> CommitOptions {
+        let sign = if self.no_gpg_sign {
+            Some(false)
+        } else if self.gpg_sign.is_some() {
+            Some(true)
+        } else {
+            None
+        };
+
+        CommitOptions {
+            sign,
+            signing_key: self.gpg_sign.clone().filter(|key| !key.is_empty()),
+            no_verify: self.no_verify,
+            author: self.author.clone(),
+            date: self.date.clone(),
+            cleanup: self.cleanup.clone(),
+            ..Default::default()
         }
-        None => LlmProvider::detect_from_config(&config).ok_or_else(|| {
-            anyhow::anyhow!(
-                "No LLM provider configured. Please configure a provider in config file or environment variables, or use --llm flag to specify provider."
-            )
-        })?,
-    };
+    }
+}
+
+#[derive(Args)]
+pub struct CommitArgs {
+    /// Apply the commit after generating the message
+    #[arg(short, long)]
+    pub apply: bool,
+
+    /// Include the whole enclosing function of each changed hunk
+    #[arg(short = 'W', long)]
+    pub function_context: bool,
+
+    /// Message style preset or a style defined under `[styles]`
+    #[arg(long, value_name = "STYLE")]
+    pub style: Option<String>,
+
+    /// Language of the subject and body, e.g. `zh-TW` (overrides the config)
+    #[arg(long, value_name = "LANG")]
+    pub lang: Option<String>,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+
+    #[command(flatten)]
+    pub flags: CommitFlags,
+
+    /// Allow recording a commit without changes
+    #[arg(long)]
+    pub allow_empty: bool,
+
+    /// Always ask the provider instead of reusing a cached message
+    #[arg(long)]
+    pub no_cache: bool,
 
-    // Create LLM generater from config
-    let llm_generater: Box<dyn LlmGenerater> =
-        provider.create_llm_generater_from_config(&config)?;
+    /// Print the request that would be sent to the provider as JSON and exit
+    #[arg(long, visible_alias = "print-prompt", conflicts_with_all = ["apply", "review"])]
+    pub dry_run: bool,
+
+    /// Review the staged changes before applying the commit
+    #[arg(long, requires = "apply")]
+    pub review: bool,
+
+    /// Commit with the message saved by the last failed commit instead of generating one
+    ///
+    /// The saved message already carries its trailers, so the trailer flags are rejected.
+    #[arg(
+        long,
+        conflicts_with_all = ["dry_run", "review", "signoff", "co_authors", "pick_co_authors", "trailers"]
+    )]
+    pub reuse: bool,
+
+    /// Extra arguments passed to git commit, given after `--`
+    #[arg(last = true, value_name = "GIT_ARGS")]
+    pub git_args: Vec<String>,
+}
+
+impl CommitArgs {
+    /// Build the options passed to `git commit`
+    fn commit_options(&self) -> CommitOptions {
+        CommitOptions {
+            allow_empty: self.allow_empty,
+            extra_args: self.git_args.clone(),
+            ..self.flags.commit_options()
+        }
+    }
+}
+
+pub async fn run(args: CommitArgs, config: AppConfig, usage: &UsageTracker) -> anyhow::Result<()> {
+    if args.reuse {
+        return reuse_saved_message(args.commit_options()).await;
+    }
+
+    let apply = args.apply;
+
+    // Determine LLM provider
+    let provider = LlmProvider::resolve(args.llm.clone(), &config)?;
+
+    // Create LLM generater from config, answering repeated requests from the cache
+    let mut llm_generater: Box<dyn LlmGenerater> =
+        provider.create_llm_generater_from_config(&config, usage)?;
+    if !args.no_cache
+        && let Some(dir) = AppConfig::get_cache_dir()
+    {
+        llm_generater = Box::new(CachedLlmGenerater::new(llm_generater, provider.name(), dir));
+    }
 
     // Create git repository
     let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
 
+    let style = config.message_style(args.style.as_deref())?;
+    let mut context = config.context_options();
+    context.function_context |= args.function_context;
+
     // Create commit service
-    let commit_service = CommitServiceImpl::new(llm_generater, git_repository);
+    let commit_service = build_commit_service(
+        llm_generater,
+        git_repository,
+        &config,
+        &args.flags,
+        style,
+        args.lang.as_deref(),
+    )
+    .await?
+    .with_context(context)
+    .with_commit_options(args.commit_options());
+
+    if args.dry_run {
+        let preview = commit_service
+            .preview()
+            .await
+            .map_err(|e| anyhow!("Failed to build request: {}", e))?;
+        println!("{}", serde_json::to_string_pretty(&preview)?);
+        return Ok(());
+    }
 
     // Generate commit message first (without applying)
     let message = match commit_service.commit(false).await {
@@ -53,8 +243,25 @@ pub async fn run(
     // Display the generated commit message
     println!("{}", message);
 
+    // Keep the message so a failed commit does not lose it
+    let history_id = journal::record_generated(
+        "commit",
+        provider.name(),
+        provider.model(&config).unwrap_or_default(),
+        &message,
+    )
+    .await;
+
     // If apply is requested, ask for confirmation
     if apply {
+        if (args.review || config.review_before_apply())
+            && !review_gate(args.llm.clone(), &config, usage).await?
+        {
+            journal::update_status(history_id, MessageStatus::Rejected);
+            println!("Commit cancelled.");
+            return Ok(());
+        }
+
         print!("Apply this commit? [Y/n]: ");
         io::stdout().flush()?;
 
@@ -67,12 +274,24 @@ pub async fn run(
             // Apply the commit through commit service
             match commit_service.apply_commit(&message).await {
                 Ok(_) => {
+                    journal::update_status(history_id, journal::committed_status(&message).await);
                     println!("✓ Commit applied successfully");
                     Ok(())
                 }
-                Err(e) => Err(anyhow!("Failed to apply commit: {}", e)),
+                Err(e) => {
+                    journal::update_status(history_id, MessageStatus::Failed);
+                    // Saving can fail too; only point at --reuse if the message is really there
+                    let saved = commit_service.saved_message().await.ok().flatten();
+                    if saved.as_deref().map(str::trim_end) == Some(message.trim_end()) {
+                        eprintln!(
+                            "ℹ The message was saved; fix the problem and retry with `git gen commit --reuse`"
+                        );
+                    }
+                    Err(anyhow!("Failed to apply commit: {}", e))
+                }
             }
         } else {
+            journal::update_status(history_id, MessageStatus::Rejected);
             println!("ℹ Commit cancelled");
             Ok(())
         }
@@ -81,3 +300,145 @@ pub async fn run(
         Ok(())
     }
 }
+
+/// Commit with the message kept by the last failed commit, without generating
+async fn reuse_saved_message(options: CommitOptions) -> anyhow::Result<()> {
+    let git_repository = GitRepositoryImpl::new();
+    let message = git_repository
+        .saved_message()
+        .await?
+        .ok_or_else(|| anyhow!("No saved message; it is only kept when a commit fails"))?;
+    println!("{}", message);
+
+    print!("Apply this commit? [Y/n]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    if !(input.is_empty() || input == "y" || input == "yes") {
+        println!("ℹ Commit cancelled");
+        return Ok(());
+    }
+
+    // The saved message stays in place until a commit with it succeeds
+    git_repository
+        .commit(&message, &options)
+        .await
+        .map_err(|e| anyhow!("Failed to apply commit: {}", e))?;
+    git_repository.clear_saved_message().await?;
+    println!("✓ Commit applied successfully");
+    Ok(())
+}
+
+/// Build the commit service from the config and the shared commit flags
+///
+/// `split` uses the same service so its commits get the same validation,
+/// ticket reference, trailers and `git commit` options.
+pub async fn build_commit_service(
+    llm_generater: Box<dyn LlmGenerater>,
+    git_repository: Box<dyn GitRepository>,
+    config: &AppConfig,
+    flags: &CommitFlags,
+    style: MessageStyle,
+    lang: Option<&str>,
+) -> anyhow::Result<CommitServiceImpl> {
+    // Repository root is needed for commitlint configs and workspace scopes
+    let root = git_repository.root_dir().await?;
+
+    let rules = resolve_rules(config, &root, &style)?;
+    let language = match lang {
+        Some(lang) => Some(lang.to_string()),
+        None => config.language(&root)?,
+    };
+
+    // Collect trailers from config and command line
+    let mut trailers = config.trailers()?;
+    for co_author in &flags.co_authors {
+        trailers.push(co_author_trailer(co_author));
+    }
+    if flags.pick_co_authors {
+        trailers.extend(pick_co_authors(git_repository.as_ref()).await?);
+    }
+    for trailer in &flags.trailers {
+        trailers.push(parse_trailer(trailer)?);
+    }
+
+    Ok(CommitServiceImpl::new(llm_generater, git_repository)
+        .with_rules(rules)
+        .with_style(style)
+        .with_language(language)
+        .with_max_retries(config.max_retries())
+        .with_scope_mappings(config.scope_mappings(&root)?)
+        .with_ticket(config.ticket_reference()?)
+        .with_sign_off(flags.signoff || config.sign_off())
+        .with_trailers(trailers)
+        .with_commit_options(flags.commit_options()))
+}
+
+/// The style's rules with the configured overrides and the repository's
+/// commitlint config merged in
+pub fn resolve_rules(
+    config: &AppConfig,
+    root: &Path,
+    style: &MessageStyle,
+) -> anyhow::Result<Option<CommitRules>> {
+    let mut rules = config.commit_rules(style);
+    // commitlint only describes Conventional Commits headers
+    if let Some(rules) = rules.as_mut()
+        && rules.header == HeaderFormat::Conventional
+        && config.use_commitlint()
+        && let Some(commitlint) = CommitlintConfig::load(root)?
+    {
+        commitlint.apply(rules);
+    }
+    Ok(rules)
+}
+
+/// Build a `Co-authored-by` trailer
+fn co_author_trailer(author: &str) -> Footer {
+    Footer {
+        token: "Co-authored-by".to_string(),
+        value: author.trim().to_string(),
+    }
+}
+
+/// Let the user choose co-authors from recent commit authors
+async fn pick_co_authors(git_repository: &dyn GitRepository) -> anyhow::Result<Vec<Footer>> {
+    let me = git_repository.committer_identity().await.ok();
+    let authors: Vec<String> = git_repository
+        .recent_authors(CO_AUTHOR_CANDIDATES + 1)
+        .await?
+        .into_iter()
+        .filter(|author| Some(author) != me.as_ref())
+        .take(CO_AUTHOR_CANDIDATES)
+        .collect();
+
+    if authors.is_empty() {
+        println!("ℹ No recent authors to pick from");
+        return Ok(Vec::new());
+    }
+
+    for (index, author) in authors.iter().enumerate() {
+        println!("  {}) {}", index + 1, author);
+    }
+    print!("Co-authors (comma-separated numbers, empty for none): ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|choice| !choice.is_empty())
+        .map(|choice| {
+            choice
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| authors.get(n.wrapping_sub(1)))
+                .map(|author| co_author_trailer(author))
+                .ok_or_else(|| anyhow!("Invalid selection: {}", choice))
+        })
+        .collect()
+}
@@ -0,0 +1,72 @@
+use anyhow::anyhow;
+use clap::Args;
+use git_gen_core::{ExplainService, ExplainServiceImpl, GitRepository, LlmGenerater};
+use git_gen_git::GitRepositoryImpl;
+
+use crate::config::AppConfig;
+use crate::ledger::UsageTracker;
+use crate::llm_provider::LlmProvider;
+
+#[derive(Args)]
+pub struct ExplainArgs {
+    /// Commit or range to explain, e.g. `abc123` or `v1.0..v1.1`
+    #[arg(default_value = "HEAD")]
+    pub target: String,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+}
+
+pub async fn run(args: ExplainArgs, config: AppConfig, usage: &UsageTracker) -> anyhow::Result<()> {
+    let range = to_range(&args.target);
+
+    let provider = LlmProvider::resolve(args.llm.clone(), &config)?;
+    let llm_generater: Box<dyn LlmGenerater> =
+        provider.create_llm_generater_from_config(&config, usage)?;
+    let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
+
+    let explain_service = ExplainServiceImpl::new(llm_generater, git_repository);
+    match explain_service.explain(&range).await {
+        Ok(explanation) => {
+            println!("{}", explanation);
+            Ok(())
+        }
+        Err(e) => Err(anyhow!("Failed to explain {}: {}", args.target, e)),
+    }
+}
+
+/// A single revision means just that commit (`<rev>^!`)
+///
+/// Only real ranges pass through: `a..b`, `a...b` and the `^!`, `^@` and
+/// `^-[<n>]` suffixes. `HEAD^` is a single revision; as a range it would
+/// select the whole history.
+fn to_range(target: &str) -> String {
+    let without_count = target.trim_end_matches(|c: char| c.is_ascii_digit());
+    if target.contains("..")
+        || target.ends_with("^!")
+        || target.ends_with("^@")
+        || without_count.ends_with("^-")
+    {
+        target.to_string()
+    } else {
+        format!("{}^!", target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_range() {
+        assert_eq!(to_range("abc123"), "abc123^!");
+        assert_eq!(to_range("v1.0..v1.1"), "v1.0..v1.1");
+        assert_eq!(to_range("HEAD~2^!"), "HEAD~2^!");
+        assert_eq!(to_range("HEAD^@"), "HEAD^@");
+        assert_eq!(to_range("HEAD^-2"), "HEAD^-2");
+        assert_eq!(to_range("HEAD^"), "HEAD^^!");
+        assert_eq!(to_range("HEAD~1^"), "HEAD~1^^!");
+        assert_eq!(to_range("abc123^2"), "abc123^2^!");
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+use git_gen_core::{CommitOptions, GitRepository};
+use git_gen_git::GitRepositoryImpl;
+use std::io::{self, Write};
+
+use crate::config::AppConfig;
+use crate::journal::{self, Journal, JournalEntry, MessageStatus};
+
+/// Number of entries listed by default
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Args)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub command: Option<HistoryCommand>,
+
+    /// Number of entries to list
+    #[arg(short = 'n', long, default_value_t = DEFAULT_LIMIT)]
+    pub limit: usize,
+
+    /// List entries of all repositories, not just the current one
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Subcommand)]
+pub enum HistoryCommand {
+    /// Print the full message of an entry
+    Show {
+        /// Entry id from the list
+        id: u64,
+    },
+    /// Commit the staged changes with the message of an entry
+    Reuse {
+        /// Entry id from the list
+        id: u64,
+
+        /// Commit without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Bypass the pre-commit and commit-msg hooks
+        #[arg(short = 'n', long)]
+        no_verify: bool,
+    },
+}
+
+pub async fn run(args: HistoryArgs, _config: AppConfig) -> anyhow::Result<()> {
+    let journal = Journal::open()
+        .ok_or_else(|| anyhow!("Failed to locate the history: no home directory"))?;
+
+    match args.command {
+        None => list(&journal, args.limit, args.all).await,
+        Some(HistoryCommand::Show { id }) => {
+            println!("{}", find(&journal, id)?.message);
+            Ok(())
+        }
+        Some(HistoryCommand::Reuse { id, yes, no_verify }) => {
+            reuse(&journal, id, yes, no_verify).await
+        }
+    }
+}
+
+async fn list(journal: &Journal, limit: usize, all: bool) -> anyhow::Result<()> {
+    let repo = if all {
+        None
+    } else {
+        GitRepositoryImpl::new()
+            .root_dir()
+            .await
+            .ok()
+            .map(|root| root.display().to_string())
+    };
+
+    let entries: Vec<JournalEntry> = journal
+        .load()?
+        .into_iter()
+        .rev()
+        .filter(|e| repo.is_none() || e.repo == repo)
+        .take(limit)
+        .collect();
+    if entries.is_empty() {
+        println!("ℹ No generated messages recorded");
+        return Ok(());
+    }
+
+    for entry in entries {
+        let location = if all {
+            format!("  {}", entry.repo.as_deref().unwrap_or("-"))
+        } else {
+            String::new()
+        };
+        println!(
+            "{:>4}  {}  {:<6}  {:<9}{}  {}",
+            entry.id,
+            entry.date,
+            entry.command,
+            entry.status.as_str(),
+            location,
+            entry.subject()
+        );
+    }
+    Ok(())
+}
+
+fn find(journal: &Journal, id: u64) -> anyhow::Result<JournalEntry> {
+    journal
+        .get(id)?
+        .ok_or_else(|| anyhow!("No history entry with id {}", id))
+}
+
+async fn reuse(journal: &Journal, id: u64, yes: bool, no_verify: bool) -> anyhow::Result<()> {
+    let entry = find(journal, id)?;
+    if !journal::COMMIT_COMMANDS.contains(&entry.command.as_str()) {
+        return Err(anyhow!(
+            "History entry {} is a {} suggestion, not a commit message",
+            id,
+            entry.command
+        ));
+    }
+    println!("{}", entry.message);
+
+    if !yes {
+        print!("Commit with this message? [Y/n]: ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+        if !(input.is_empty() || input == "y" || input == "yes") {
+            println!("ℹ Commit cancelled");
+            return Ok(());
+        }
+    }
+
+    let options = CommitOptions {
+        no_verify,
+        ..Default::default()
+    };
+    match GitRepositoryImpl::new()
+        .commit(&entry.message, &options)
+        .await
+    {
+        Ok(_) => {
+            journal::update_status(Some(id), journal::committed_status(&entry.message).await);
+            println!("✓ Commit applied successfully");
+            Ok(())
+        }
+        Err(e) => {
+            journal::update_status(Some(id), MessageStatus::Failed);
+            Err(anyhow!("Failed to apply commit: {}", e))
+        }
+    }
+}
@@ -1 +1,12 @@
+pub mod branch;
+pub mod bump;
+pub mod cache;
+pub mod changelog;
 pub mod commit;
+pub mod explain;
+pub mod history;
+pub mod pr;
+pub mod review;
+pub mod split;
+pub mod squash;
+pub mod usage;
@@ -0,0 +1,93 @@
+use anyhow::{Context, anyhow};
+use clap::Args;
+use git_gen_core::{GitRepository, LlmGenerater, PrService, PrServiceImpl};
+use git_gen_git::GitRepositoryImpl;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+use crate::journal;
+use crate::ledger::UsageTracker;
+use crate::llm_provider::LlmProvider;
+
+/// Locations GitHub looks for a pull request template, in order
+const TEMPLATE_PATHS: &[&str] = &[
+    ".github/pull_request_template.md",
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+];
+
+#[derive(Args)]
+pub struct PrArgs {
+    /// Branch the pull request will be merged into
+    #[arg(short, long, default_value = "main")]
+    pub base: String,
+
+    /// Revision with the changes to describe
+    #[arg(long, default_value = "HEAD")]
+    pub head: String,
+
+    /// Write the description to a file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Ignore the repository's pull request template
+    #[arg(long)]
+    pub no_template: bool,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+}
+
+pub async fn run(args: PrArgs, config: AppConfig, usage: &UsageTracker) -> anyhow::Result<()> {
+    let provider = LlmProvider::resolve(args.llm.clone(), &config)?;
+    let llm_generater: Box<dyn LlmGenerater> =
+        provider.create_llm_generater_from_config(&config, usage)?;
+    let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
+
+    let template = if args.no_template {
+        None
+    } else {
+        load_template(&git_repository.root_dir().await?)?
+    };
+
+    let pr_service = PrServiceImpl::new(llm_generater, git_repository).with_template(template);
+    let pull_request = match pr_service.generate(&args.base, &args.head).await {
+        Ok(pr) => pr,
+        Err(e) => return Err(anyhow!("Failed to generate pull request: {}", e)),
+    };
+    journal::record_generated(
+        "pr",
+        provider.name(),
+        provider.model(&config).unwrap_or_default(),
+        &format!("{}\n\n{}", pull_request.title, pull_request.body),
+    )
+    .await;
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, pull_request.to_markdown())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("✓ Pull request written to {}", path.display());
+        }
+        None => print!("{}", pull_request.to_markdown()),
+    }
+    Ok(())
+}
+
+/// Read the first pull request template found in the repository
+fn load_template(root: &Path) -> anyhow::Result<Option<String>> {
+    for candidate in TEMPLATE_PATHS {
+        let path = root.join(candidate);
+        if path.is_file() {
+            let template = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            return Ok(Some(template));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,200 @@
+use anyhow::anyhow;
+use clap::{Args, ValueEnum};
+use git_gen_core::{
+    GitRepository, LlmGenerater, Review, ReviewService, ReviewServiceImpl, Severity,
+};
+use git_gen_git::GitRepositoryImpl;
+use serde_json::json;
+
+use crate::config::AppConfig;
+use crate::ledger::UsageTracker;
+use crate::llm_provider::LlmProvider;
+
+/// Output format of the review
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum ReviewFormat {
+    /// Findings grouped by file
+    #[default]
+    Text,
+    /// The findings as JSON
+    Json,
+    /// SARIF 2.1.0 for code scanning tools
+    Sarif,
+}
+
+/// Severity at which the command exits with an error
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum FailOn {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Args)]
+pub struct ReviewArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ReviewFormat::Text)]
+    pub format: ReviewFormat,
+
+    /// Exit with an error if a finding of this severity or above is reported
+    #[arg(long, value_enum, value_name = "SEVERITY")]
+    pub fail_on: Option<FailOn>,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+}
+
+pub async fn run(args: ReviewArgs, config: AppConfig, usage: &UsageTracker) -> anyhow::Result<()> {
+    let review = review_staged(args.llm.clone(), &config, usage).await?;
+
+    match args.format {
+        ReviewFormat::Text => print_review(&review),
+        ReviewFormat::Json => println!("{}", serde_json::to_string_pretty(&review)?),
+        ReviewFormat::Sarif => println!("{}", serde_json::to_string_pretty(&to_sarif(&review))?),
+    }
+
+    if let Some(fail_on) = args.fail_on {
+        let severity = match fail_on {
+            FailOn::Error => Severity::Error,
+            FailOn::Warning => Severity::Warning,
+            FailOn::Info => Severity::Info,
+        };
+        let count = review.count_at_least(severity);
+        if count > 0 {
+            return Err(anyhow!(
+                "Review reported {} finding(s) at {} or above",
+                count,
+                severity.as_str()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Review the staged changes with the given or configured provider
+pub async fn review_staged(
+    llm: Option<LlmProvider>,
+    config: &AppConfig,
+    usage: &UsageTracker,
+) -> anyhow::Result<Review> {
+    let provider = LlmProvider::resolve(llm, config)?;
+    let llm_generater: Box<dyn LlmGenerater> =
+        provider.create_llm_generater_from_config(config, usage)?;
+    let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
+
+    let review_service = ReviewServiceImpl::new(llm_generater, git_repository);
+    match review_service.review().await {
+        Ok(review) => Ok(review),
+        Err(e) => Err(anyhow!("Failed to review staged changes: {}", e)),
+    }
+}
+
+/// Print findings grouped by file
+pub fn print_review(review: &Review) {
+    if review.findings.is_empty() {
+        println!("✓ No problems found");
+        return;
+    }
+
+    let mut current_file: Option<&str> = None;
+    for finding in &review.findings {
+        if current_file != Some(finding.file.as_str()) {
+            if current_file.is_some() {
+                println!();
+            }
+            println!("{}", finding.file);
+            current_file = Some(finding.file.as_str());
+        }
+        let line = finding
+            .line
+            .map(|line| format!("{:>5}", line))
+            .unwrap_or_else(|| format!("{:>5}", "-"));
+        println!(
+            "  {}  {:<7} [{}] {}",
+            line,
+            finding.severity.as_str(),
+            finding.category,
+            finding.message
+        );
+    }
+}
+
+/// Convert the review to a SARIF 2.1.0 log
+fn to_sarif(review: &Review) -> serde_json::Value {
+    let mut rules: Vec<&str> = review
+        .findings
+        .iter()
+        .map(|f| f.category.as_str())
+        .collect();
+    rules.sort_unstable();
+    rules.dedup();
+
+    let results: Vec<serde_json::Value> = review
+        .findings
+        .iter()
+        .map(|finding| {
+            let level = match finding.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Info => "note",
+            };
+            let mut location = json!({ "artifactLocation": { "uri": finding.file } });
+            if let Some(line) = finding.line {
+                location["region"] = json!({ "startLine": line });
+            }
+            json!({
+                "ruleId": finding.category,
+                "level": level,
+                "message": { "text": finding.message },
+                "locations": [{ "physicalLocation": location }],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "git-gen",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_gen_core::Finding;
+
+    #[test]
+    fn test_to_sarif() {
+        let review = Review {
+            findings: vec![Finding {
+                file: "src/main.rs".to_string(),
+                line: Some(7),
+                severity: Severity::Info,
+                category: "debug".to_string(),
+                message: "Remove println!".to_string(),
+            }],
+        };
+
+        let sarif = to_sarif(&review);
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["level"], "note");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startLine"],
+            7
+        );
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "debug"
+        );
+    }
+}
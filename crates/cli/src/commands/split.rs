@@ -0,0 +1,149 @@
+use anyhow::anyhow;
+use clap::Args;
+use git_gen_core::diff::render_patches;
+use git_gen_core::{
+    CommitService, GitRepository, LlmGenerater, SplitPlan, SplitService, SplitServiceImpl,
+};
+use git_gen_git::GitRepositoryImpl;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use crate::commands::commit::{CommitFlags, build_commit_service, resolve_rules};
+use crate::config::AppConfig;
+use crate::journal::{self, MessageStatus};
+use crate::ledger::UsageTracker;
+use crate::llm_provider::LlmProvider;
+
+#[derive(Args)]
+pub struct SplitArgs {
+    /// Language of the subject and body, e.g. `zh-TW` (overrides the config)
+    #[arg(long, value_name = "LANG")]
+    pub lang: Option<String>,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+
+    /// Create the commits without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+
+    #[command(flatten)]
+    pub flags: CommitFlags,
+}
+
+pub async fn run(args: SplitArgs, config: AppConfig, usage: &UsageTracker) -> anyhow::Result<()> {
+    let provider = LlmProvider::resolve(args.llm.clone(), &config)?;
+    let llm_generater: Arc<dyn LlmGenerater> = provider
+        .create_llm_generater_from_config(&config, usage)?
+        .into();
+    let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
+
+    let root = git_repository.root_dir().await?;
+    let style = config.message_style(None)?;
+    let rules = resolve_rules(&config, &root, &style)?;
+    let language = match &args.lang {
+        Some(lang) => Some(lang.clone()),
+        None => config.language(&root)?,
+    };
+
+    // Planned messages are validated and finished like those of `commit`
+    let commit_service = build_commit_service(
+        Box::new(llm_generater.clone()),
+        Box::new(GitRepositoryImpl::new()),
+        &config,
+        &args.flags,
+        style.clone(),
+        language.as_deref(),
+    )
+    .await?;
+
+    let split_service = SplitServiceImpl::new(Box::new(llm_generater), git_repository)
+        .with_rules(rules)
+        .with_style(style)
+        .with_language(language)
+        .with_commit_options(args.flags.commit_options());
+
+    let mut plan = match split_service.plan().await {
+        Ok(plan) => plan,
+        Err(e) => return Err(anyhow!("Failed to plan the split: {}", e)),
+    };
+    let diffs: Vec<String> = plan
+        .commits
+        .iter()
+        .map(|commit| render_patches(&plan.patches_for(&commit.chunks)))
+        .collect();
+    for (commit, diff) in plan.commits.iter_mut().zip(diffs) {
+        commit.message = commit_service
+            .finish_message(diff, std::mem::take(&mut commit.message))
+            .await
+            .map_err(|e| anyhow!("Failed to plan the split: {}", e))?;
+    }
+    print_plan(&plan);
+
+    let mut history_ids = Vec::new();
+    for commit in &plan.commits {
+        history_ids.push(
+            journal::record_generated(
+                "split",
+                provider.name(),
+                provider.model(&config).unwrap_or_default(),
+                &commit.message,
+            )
+            .await,
+        );
+    }
+    let update_status = |status: MessageStatus| {
+        for id in &history_ids {
+            journal::update_status(*id, status);
+        }
+    };
+
+    if !args.yes {
+        print!("Create these {} commits? [Y/n]: ", plan.commits.len());
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+
+        if !(input.is_empty() || input == "y" || input == "yes") {
+            update_status(MessageStatus::Rejected);
+            println!("ℹ Split cancelled");
+            return Ok(());
+        }
+    }
+
+    match split_service.apply(&plan).await {
+        Ok(_) => {
+            update_status(MessageStatus::Accepted);
+            println!("✓ Created {} commits", plan.commits.len());
+            Ok(())
+        }
+        Err(e) => {
+            update_status(MessageStatus::Failed);
+            Err(anyhow!("Failed to split the commit: {:#}", e))
+        }
+    }
+}
+
+/// Show each planned commit with the files it touches
+fn print_plan(plan: &SplitPlan) {
+    for (index, commit) in plan.commits.iter().enumerate() {
+        println!("── Commit {} of {} ──", index + 1, plan.commits.len());
+        println!("{}", commit.message);
+        println!();
+        for file in plan.files_for(&commit.chunks) {
+            println!("  {}", file);
+        }
+        println!();
+    }
+
+    if !plan.unassigned.is_empty() {
+        println!("ℹ These changes are not part of any commit and stay staged:");
+        for file in plan.files_for(&plan.unassigned) {
+            println!("  {}", file);
+        }
+        println!();
+    }
+}
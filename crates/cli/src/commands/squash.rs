@@ -0,0 +1,142 @@
+use anyhow::anyhow;
+use clap::Args;
+use git_gen_core::{CommitService, CommitServiceImpl, GitRepository, LlmGenerater};
+use git_gen_git::GitRepositoryImpl;
+use std::io::{self, Write};
+
+use crate::commands::commit::resolve_rules;
+use crate::config::AppConfig;
+use crate::journal::{self, MessageStatus};
+use crate::ledger::UsageTracker;
+use crate::llm_provider::LlmProvider;
+
+#[derive(Args)]
+pub struct SquashArgs {
+    /// Commits to condense, e.g. `main..HEAD`; a single revision means `<rev>..HEAD`
+    pub range: String,
+
+    /// Replace the commits with one commit using the generated message
+    #[arg(short, long)]
+    pub apply: bool,
+
+    /// Language of the subject and body, e.g. `zh-TW` (overrides the config)
+    #[arg(long, value_name = "LANG")]
+    pub lang: Option<String>,
+
+    /// LLM provider to use (openai or gemini)
+    #[arg(short, long, value_enum)]
+    pub llm: Option<LlmProvider>,
+}
+
+pub async fn run(args: SquashArgs, config: AppConfig, usage: &UsageTracker) -> anyhow::Result<()> {
+    let (range, base) = parse_range(&args.range)?;
+
+    let provider = LlmProvider::resolve(args.llm.clone(), &config)?;
+    let llm_generater: Box<dyn LlmGenerater> =
+        provider.create_llm_generater_from_config(&config, usage)?;
+    let git_repository: Box<dyn GitRepository> = Box::new(GitRepositoryImpl::new());
+
+    let root = git_repository.root_dir().await?;
+    let style = config.message_style(None)?;
+    let rules = resolve_rules(&config, &root, &style)?;
+    let language = match &args.lang {
+        Some(lang) => Some(lang.clone()),
+        None => config.language(&root)?,
+    };
+
+    let commit_service = CommitServiceImpl::new(llm_generater, git_repository)
+        .with_rules(rules)
+        .with_style(style)
+        .with_language(language)
+        .with_max_retries(config.max_retries())
+        .with_ticket(config.ticket_reference()?)
+        .with_sign_off(config.sign_off())
+        .with_trailers(config.trailers()?);
+
+    let message = match commit_service.squash_message(&range).await {
+        Ok(msg) => msg,
+        Err(e) => return Err(anyhow!("Failed to generate squash message: {}", e)),
+    };
+    println!("{}", message);
+
+    let history_id = journal::record_generated(
+        "squash",
+        provider.name(),
+        provider.model(&config).unwrap_or_default(),
+        &message,
+    )
+    .await;
+
+    if !args.apply {
+        println!("ℹ Use --apply to squash the commits");
+        return Ok(());
+    }
+    let Some(base) = base else {
+        return Err(anyhow!(
+            "--apply only works for ranges ending at HEAD, e.g. main..HEAD"
+        ));
+    };
+
+    print!("Squash {} into one commit? [Y/n]: ", range);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+
+    if input.is_empty() || input == "y" || input == "yes" {
+        match commit_service.apply_squash(&base, &message).await {
+            Ok(_) => {
+                journal::update_status(history_id, journal::committed_status(&message).await);
+                println!("✓ Commits squashed successfully");
+                Ok(())
+            }
+            Err(e) => {
+                journal::update_status(history_id, MessageStatus::Failed);
+                Err(anyhow!("Failed to squash commits: {}", e))
+            }
+        }
+    } else {
+        journal::update_status(history_id, MessageStatus::Rejected);
+        println!("ℹ Squash cancelled");
+        Ok(())
+    }
+}
+
+/// Normalize the range and return its base if it ends at HEAD
+fn parse_range(range: &str) -> anyhow::Result<(String, Option<String>)> {
+    if range.contains("...") {
+        return Err(anyhow!(
+            "Symmetric ranges like {} cannot be squashed; use <base>..<tip>",
+            range
+        ));
+    }
+
+    match range.split_once("..") {
+        Some(("", _)) => Err(anyhow!("Range {} has no base revision", range)),
+        Some((base, tip)) => {
+            let ends_at_head = tip.is_empty() || tip == "HEAD";
+            Ok((range.to_string(), ends_at_head.then(|| base.to_string())))
+        }
+        None => Ok((format!("{}..HEAD", range), Some(range.to_string()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            parse_range("main").unwrap(),
+            ("main..HEAD".to_string(), Some("main".to_string()))
+        );
+        assert_eq!(
+            parse_range("HEAD~3..").unwrap(),
+            ("HEAD~3..".to_string(), Some("HEAD~3".to_string()))
+        );
+        assert_eq!(parse_range("v1.0..v1.1").unwrap().1, None);
+        assert!(parse_range("main...HEAD").is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::anyhow;
+use clap::{Args, ValueEnum};
+use git_gen_core::TokenUsage;
+use std::collections::BTreeMap;
+
+use crate::config::AppConfig;
+use crate::ledger::{self, LedgerEntry};
+
+/// Column the usage totals are grouped by
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum UsageGroup {
+    Day,
+    Repo,
+    Team,
+    Model,
+    Command,
+}
+
+impl UsageGroup {
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Day => "DAY",
+            Self::Repo => "REPO",
+            Self::Team => "TEAM",
+            Self::Model => "MODEL",
+            Self::Command => "COMMAND",
+        }
+    }
+
+    fn key(self, entry: &LedgerEntry) -> String {
+        let value = match self {
+            Self::Day => Some(&entry.date),
+            Self::Repo => entry.repo.as_ref(),
+            Self::Team => entry.team.as_ref(),
+            Self::Model => Some(&entry.model),
+            Self::Command => Some(&entry.command),
+        };
+        value.cloned().unwrap_or_else(|| "-".to_string())
+    }
+}
+
+#[derive(Args)]
+pub struct UsageArgs {
+    /// Only count runs on or after this day (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    /// Columns to group by, comma-separated
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [UsageGroup::Day, UsageGroup::Repo, UsageGroup::Model]
+    )]
+    pub by: Vec<UsageGroup>,
+}
+
+/// Token and cost totals of a group of runs
+#[derive(Debug, Default, PartialEq)]
+struct Totals {
+    runs: usize,
+    usage: TokenUsage,
+    /// Cost of the runs with a price; `None` when no run had one
+    cost: Option<f64>,
+    /// Runs of models without a price, whose cost is not in `cost`
+    unpriced: usize,
+}
+
+impl Totals {
+    fn add(&mut self, other: &Totals) {
+        self.runs += other.runs;
+        self.usage += other.usage;
+        self.cost = add_cost(self.cost, other.cost);
+        self.unpriced += other.unpriced;
+    }
+
+    /// The cost, marked when runs without a price are missing from it
+    fn cost_label(&self) -> String {
+        match self.cost {
+            None => "unknown".to_string(),
+            Some(cost) if self.unpriced > 0 => format!("${:.4} (partial)", cost),
+            Some(cost) => format!("${:.4}", cost),
+        }
+    }
+}
+
+pub async fn run(args: UsageArgs, config: AppConfig) -> anyhow::Result<()> {
+    let path = AppConfig::get_usage_ledger_path()
+        .ok_or_else(|| anyhow!("Failed to locate the usage ledger: no home directory"))?;
+    let entries = ledger::load(&path)?;
+    let entries: Vec<LedgerEntry> = entries
+        .into_iter()
+        .filter(|e| args.since.as_ref().is_none_or(|since| e.date >= *since))
+        .collect();
+
+    if entries.is_empty() {
+        println!("ℹ No usage recorded in {}", path.display());
+        if !config.usage_ledger() {
+            println!("ℹ Set `ledger = true` under [usage] in the config to record runs");
+        }
+        return Ok(());
+    }
+
+    let groups = summarize(&entries, &args.by);
+    let mut rows: Vec<Vec<String>> = vec![
+        args.by
+            .iter()
+            .map(|g| g.heading().to_string())
+            .chain(["RUNS", "PROMPT", "COMPLETION", "COST"].map(String::from))
+            .collect(),
+    ];
+    let mut total = Totals::default();
+    for (key, totals) in &groups {
+        rows.push(row(key.clone(), totals));
+        total.add(totals);
+    }
+    let mut total_key = vec![String::new(); args.by.len()];
+    if let Some(first) = total_key.first_mut() {
+        *first = "TOTAL".to_string();
+    }
+    rows.push(row(total_key, &total));
+
+    print_table(&rows);
+    if total.unpriced > 0 {
+        println!(
+            "ℹ {} runs used models without a price; add them under [usage.prices] to include their cost",
+            total.unpriced
+        );
+    }
+    Ok(())
+}
+
+/// Sum runs per group, keyed by the values of the grouping columns
+fn summarize(entries: &[LedgerEntry], by: &[UsageGroup]) -> BTreeMap<Vec<String>, Totals> {
+    let mut groups: BTreeMap<Vec<String>, Totals> = BTreeMap::new();
+    for entry in entries {
+        let key = by.iter().map(|g| g.key(entry)).collect();
+        groups.entry(key).or_default().add(&Totals {
+            runs: 1,
+            usage: entry.usage,
+            cost: entry.cost,
+            unpriced: usize::from(entry.cost.is_none()),
+        });
+    }
+    groups
+}
+
+fn add_cost(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
+
+fn row(mut key: Vec<String>, totals: &Totals) -> Vec<String> {
+    key.extend([
+        totals.runs.to_string(),
+        totals.usage.prompt_tokens.to_string(),
+        totals.usage.completion_tokens.to_string(),
+        totals.cost_label(),
+    ]);
+    key
+}
+
+/// Print rows as columns; the four number columns are right-aligned
+fn print_table(rows: &[Vec<String>]) {
+    let columns = rows[0].len();
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| rows.iter().map(|r| r[c].chars().count()).max().unwrap_or(0))
+        .collect();
+
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(c, cell)| {
+                if c + 4 >= columns {
+                    format!("{:>width$}", cell, width = widths[c])
+                } else {
+                    format!("{:<width$}", cell, width = widths[c])
+                }
+            })
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(date: &str, model: &str, prompt_tokens: u64, cost: Option<f64>) -> LedgerEntry {
+        LedgerEntry {
+            date: date.to_string(),
+            timestamp: 0,
+            command: "commit".to_string(),
+            repo: Some("/work/app".to_string()),
+            team: None,
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            usage: TokenUsage {
+                prompt_tokens,
+                completion_tokens: 10,
+            },
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let entries = vec![
+            entry("2026-10-01", "gpt-4o-mini", 100, Some(0.5)),
+            entry("2026-10-01", "gpt-4o-mini", 200, None),
+            entry("2026-10-02", "gemini-pro", 300, None),
+        ];
+
+        let groups = summarize(&entries, &[UsageGroup::Model, UsageGroup::Team]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups[&vec!["gpt-4o-mini".to_string(), "-".to_string()]],
+            Totals {
+                runs: 2,
+                usage: TokenUsage {
+                    prompt_tokens: 300,
+                    completion_tokens: 20,
+                },
+                cost: Some(0.5),
+                unpriced: 1,
+            }
+        );
+        let gemini = &groups[&vec!["gemini-pro".to_string(), "-".to_string()]];
+        assert_eq!(gemini.cost, None);
+        assert_eq!(gemini.cost_label(), "unknown");
+        assert_eq!(
+            groups[&vec!["gpt-4o-mini".to_string(), "-".to_string()]].cost_label(),
+            "$0.5000 (partial)"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use anyhow::{Context, Result};
+use git_gen_core::CommitRules;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// JSON/YAML commitlint config files, in the order commitlint searches them
+const CONFIG_FILES: &[&str] = &[
+    ".commitlintrc",
+    ".commitlintrc.json",
+    ".commitlintrc.yaml",
+    ".commitlintrc.yml",
+];
+
+/// JavaScript/TypeScript configs that cannot be read without a JS runtime
+const SCRIPT_CONFIG_FILES: &[&str] = &[
+    "commitlint.config.js",
+    "commitlint.config.cjs",
+    "commitlint.config.mjs",
+    "commitlint.config.ts",
+    ".commitlintrc.js",
+    ".commitlintrc.cjs",
+    ".commitlintrc.mjs",
+    ".commitlintrc.ts",
+];
+
+/// Types enabled by `@commitlint/config-conventional`
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style", "test",
+];
+
+/// Line length limit used by `@commitlint/config-conventional`
+const CONVENTIONAL_MAX_LENGTH: usize = 100;
+
+/// Rule level commitlint reports as an error; 1 is a warning and 0 disables the rule
+const ERROR_LEVEL: u64 = 2;
+
+/// Subset of a commitlint configuration that git-gen understands
+#[derive(Debug, Default)]
+pub struct CommitlintConfig {
+    /// Shared configs this config extends
+    pub extends: Vec<String>,
+    /// Rule name to `[level, applicable, value]`
+    pub rules: serde_json::Map<String, Value>,
+}
+
+impl CommitlintConfig {
+    /// Find and parse a commitlint config in the repository root
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        for name in CONFIG_FILES {
+            let path = root.join(name);
+            if path.exists() {
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                // YAML is a superset of JSON, so one parser covers every file
+                let value = parse_yaml(&content)
+                    .with_context(|| format!("Failed to parse {}", path.display()))?;
+                return Ok(Some(Self::from_value(value)));
+            }
+        }
+
+        let package_json = root.join("package.json");
+        if package_json.exists() {
+            let content = fs::read_to_string(&package_json)
+                .with_context(|| format!("Failed to read {}", package_json.display()))?;
+            let value: Value = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", package_json.display()))?;
+            if let Some(commitlint) = value.get("commitlint") {
+                return Ok(Some(Self::from_value(commitlint.clone())));
+            }
+        }
+
+        if let Some(script) = SCRIPT_CONFIG_FILES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.exists())
+        {
+            eprintln!(
+                "Warning: {} is a JavaScript config and cannot be read; use .commitlintrc.json or .commitlintrc.yaml instead",
+                script.display()
+            );
+        }
+
+        Ok(None)
+    }
+
+    fn from_value(value: Value) -> Self {
+        let extends = match value.get("extends") {
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let rules = value
+            .get("rules")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+
+        Self { extends, rules }
+    }
+
+    /// Whether this config extends `@commitlint/config-conventional`
+    fn extends_conventional(&self) -> bool {
+        self.extends
+            .iter()
+            .any(|e| e.contains("config-conventional"))
+    }
+
+    /// Look up a rule enforced as an error, returning its `always`/`never` flag and value
+    fn rule(&self, name: &str) -> Option<(bool, Option<&Value>)> {
+        let rule = self.rules.get(name)?.as_array()?;
+        if rule.first()?.as_u64()? != ERROR_LEVEL {
+            return None;
+        }
+        let always = rule.get(1).and_then(Value::as_str).unwrap_or("always") == "always";
+        Some((always, rule.get(2)))
+    }
+
+    /// Whether a rule is explicitly disabled or downgraded to a warning
+    fn is_relaxed(&self, name: &str) -> bool {
+        self.rules
+            .get(name)
+            .and_then(Value::as_array)
+            .and_then(|rule| rule.first())
+            .and_then(Value::as_u64)
+            .is_some_and(|level| level < ERROR_LEVEL)
+    }
+
+    /// Apply the supported rules on top of the given validation rules
+    pub fn apply(&self, rules: &mut CommitRules) {
+        if self.extends_conventional() {
+            rules.types = CONVENTIONAL_TYPES.iter().map(|t| t.to_string()).collect();
+            rules.max_subject_length = CONVENTIONAL_MAX_LENGTH;
+            rules.max_body_line_length = Some(CONVENTIONAL_MAX_LENGTH);
+        }
+
+        if let Some((true, Some(types))) = self.rule("type-enum") {
+            rules.types = string_list(types);
+        } else if self.is_relaxed("type-enum") {
+            rules.types.clear();
+        }
+
+        if let Some((true, Some(scopes))) = self.rule("scope-enum") {
+            rules.scopes = string_list(scopes);
+        } else if self.is_relaxed("scope-enum") {
+            rules.scopes.clear();
+        }
+
+        // `scope-empty: never` means a scope is required
+        if let Some((always, _)) = self.rule("scope-empty") {
+            rules.require_scope = !always;
+        }
+
+        if let Some((true, Some(max))) = self.rule("header-max-length")
+            && let Some(max) = max.as_u64()
+        {
+            rules.max_subject_length = max as usize;
+        }
+
+        if let Some((true, Some(max))) = self.rule("body-max-line-length")
+            && let Some(max) = max.as_u64()
+        {
+            rules.max_body_line_length = Some(max as usize);
+        } else if self.is_relaxed("body-max-line-length") {
+            rules.max_body_line_length = None;
+        }
+    }
+}
+
+/// Parse the first document of a YAML (or JSON) file into a JSON value
+fn parse_yaml(content: &str) -> Result<Value> {
+    let documents = YamlLoader::load_from_str(content)?;
+    Ok(documents.first().map_or(Value::Null, yaml_to_json))
+}
+
+fn yaml_to_json(yaml: &Yaml) -> Value {
+    match yaml {
+        Yaml::Integer(n) => Value::from(*n),
+        Yaml::Real(_) => yaml.as_f64().map_or(Value::Null, Value::from),
+        Yaml::String(s) => Value::String(s.clone()),
+        Yaml::Boolean(b) => Value::Bool(*b),
+        Yaml::Array(items) => Value::Array(items.iter().map(yaml_to_json).collect()),
+        Yaml::Hash(hash) => Value::Object(
+            hash.iter()
+                .filter_map(|(key, value)| {
+                    let key = match key {
+                        Yaml::String(s) => s.clone(),
+                        Yaml::Integer(n) => n.to_string(),
+                        Yaml::Boolean(b) => b.to_string(),
+                        _ => return None,
+                    };
+                    Some((key, yaml_to_json(value)))
+                })
+                .collect(),
+        ),
+        Yaml::Null | Yaml::Alias(_) | Yaml::BadValue => Value::Null,
+    }
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> CommitlintConfig {
+        CommitlintConfig::from_value(parse_yaml(content).unwrap())
+    }
+
+    #[test]
+    fn test_json_rules() {
+        let config = parse(
+            r#"{
+                "extends": ["@commitlint/config-conventional"],
+                "rules": {
+                    "type-enum": [2, "always", ["feat", "fix"]],
+                    "scope-enum": [2, "always", ["api", "web"]],
+                    "scope-empty": [2, "never"],
+                    "header-max-length": [2, "always", 60]
+                }
+            }"#,
+        );
+        let mut rules = CommitRules::default();
+        config.apply(&mut rules);
+
+        assert_eq!(rules.types, vec!["feat", "fix"]);
+        assert_eq!(rules.scopes, vec!["api", "web"]);
+        assert!(rules.require_scope);
+        assert_eq!(rules.max_subject_length, 60);
+        assert_eq!(rules.max_body_line_length, Some(100));
+    }
+
+    #[test]
+    fn test_yaml_rules_and_disabled() {
+        let config = parse(
+            "extends: '@commitlint/config-conventional'\nrules:\n  type-enum: [0]\n  body-max-line-length: [0, always, 100]\n",
+        );
+        let mut rules = CommitRules::default();
+        config.apply(&mut rules);
+
+        assert!(rules.types.is_empty());
+        assert_eq!(rules.max_body_line_length, None);
+        assert_eq!(rules.max_subject_length, 100);
+    }
+
+    #[test]
+    fn test_warning_rules_not_enforced() {
+        let config = parse(
+            r#"{
+                "extends": ["@commitlint/config-conventional"],
+                "rules": {
+                    "type-enum": [1, "always", ["feat"]],
+                    "scope-enum": [1, "always", ["api"]],
+                    "scope-empty": [1, "never"],
+                    "header-max-length": [1, "always", 50]
+                }
+            }"#,
+        );
+        let mut rules = CommitRules::default();
+        config.apply(&mut rules);
+
+        assert!(rules.types.is_empty());
+        assert!(rules.scopes.is_empty());
+        assert!(!rules.require_scope);
+        assert_eq!(rules.max_subject_length, 100);
+    }
+}
@@ -1,11 +1,22 @@
 use anyhow::{Context, Result, anyhow};
+use git_gen_core::branch::DEFAULT_BRANCH_PATTERN;
+use git_gen_core::message::parser::parse_footer;
+use git_gen_core::message::style::PRESETS;
+use git_gen_core::message::ticket::{
+    DEFAULT_FOOTER_TEMPLATE, DEFAULT_SUBJECT_TEMPLATE, DEFAULT_TICKET_PATTERNS,
+};
+use git_gen_core::{
+    CommitRules, ContextOptions, Footer, HeaderFormat, MessageStyle, ScopeMapping, SubjectCase,
+    TicketPlacement, TicketReference,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 /// Application configuration
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct AppConfig {
     /// Default LLM provider to use
     #[serde(default)]
@@ -18,6 +29,50 @@ pub struct AppConfig {
     /// Gemini configuration
     #[serde(default)]
     pub gemini: Option<GeminiConfig>,
+
+    /// Language of commit subjects and bodies, e.g. `zh-TW`
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Commit message style: a built-in preset or a name under `[styles]`
+    #[serde(default)]
+    pub style: Option<String>,
+
+    /// User-defined message styles
+    #[serde(default)]
+    pub styles: Option<BTreeMap<String, StyleConfig>>,
+
+    /// Commit message validation rules
+    #[serde(default)]
+    pub validation: Option<ValidationConfig>,
+
+    /// Scope detection from staged file paths
+    #[serde(default)]
+    pub scopes: Option<ScopeConfig>,
+
+    /// Ticket reference extraction from the branch name
+    #[serde(default)]
+    pub ticket: Option<TicketConfig>,
+
+    /// Trailers added to every generated message
+    #[serde(default)]
+    pub trailers: Option<TrailerConfig>,
+
+    /// Extra code context sent along with the diff
+    #[serde(default)]
+    pub context: Option<ContextConfig>,
+
+    /// AI code review of staged changes
+    #[serde(default)]
+    pub review: Option<ReviewConfig>,
+
+    /// Branch name generation
+    #[serde(default)]
+    pub branch: Option<BranchConfig>,
+
+    /// Token usage ledger and model prices
+    #[serde(default)]
+    pub usage: Option<UsageConfig>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -32,6 +87,333 @@ pub struct GeminiConfig {
     pub model: String,
 }
 
+/// Commit message validation configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ValidationConfig {
+    /// Disable validation and auto-repair entirely
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Allowed commit types (defaults to the Conventional Commits types)
+    #[serde(default)]
+    pub types: Option<Vec<String>>,
+
+    /// Allowed scopes (any scope is allowed if unset)
+    #[serde(default)]
+    pub scopes: Option<Vec<String>>,
+
+    /// Require every message to have a scope
+    #[serde(default)]
+    pub scope_required: Option<bool>,
+
+    /// Maximum header length
+    #[serde(default)]
+    pub max_subject_length: Option<usize>,
+
+    /// Maximum length of body lines
+    #[serde(default)]
+    pub max_body_line_length: Option<usize>,
+
+    /// Require imperative mood in the subject
+    #[serde(default)]
+    pub imperative: Option<bool>,
+
+    /// Number of times to re-prompt the model when validation fails
+    #[serde(default)]
+    pub max_retries: Option<usize>,
+
+    /// Read commitlint configs from the repository root
+    #[serde(default)]
+    pub commitlint: Option<bool>,
+}
+
+impl ValidationConfig {
+    /// Override the style's rules, or return `None` if validation is disabled
+    pub fn to_rules(&self, defaults: CommitRules) -> Option<CommitRules> {
+        if self.enabled == Some(false) {
+            return None;
+        }
+
+        Some(CommitRules {
+            types: self.types.clone().unwrap_or(defaults.types),
+            scopes: self.scopes.clone().unwrap_or(defaults.scopes),
+            require_scope: self.scope_required.unwrap_or(defaults.require_scope),
+            max_subject_length: self
+                .max_subject_length
+                .unwrap_or(defaults.max_subject_length),
+            max_body_line_length: self.max_body_line_length.or(defaults.max_body_line_length),
+            require_imperative: self.imperative.unwrap_or(defaults.require_imperative),
+            ..defaults
+        })
+    }
+}
+
+/// A user-defined message style
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct StyleConfig {
+    /// Built-in preset the style starts from (defaults to `conventional`)
+    #[serde(default)]
+    pub base: Option<String>,
+
+    /// Header format: `conventional`, `gitmoji`, `subsystem` or `plain`
+    #[serde(default)]
+    pub header: Option<HeaderFormatConfig>,
+
+    /// Extra instructions for the model, replacing the preset's
+    #[serde(default)]
+    pub instructions: Option<Vec<String>>,
+
+    /// Allowed types, or gitmoji codes for the gitmoji header
+    #[serde(default)]
+    pub types: Option<Vec<String>>,
+
+    /// Maximum header length
+    #[serde(default)]
+    pub max_subject_length: Option<usize>,
+
+    /// Maximum length of body lines
+    #[serde(default)]
+    pub max_body_line_length: Option<usize>,
+
+    /// Allow a body after the header
+    #[serde(default)]
+    pub body: Option<bool>,
+
+    /// Force the first letter of the subject to `lower` or `upper` case
+    #[serde(default)]
+    pub subject_case: Option<SubjectCaseConfig>,
+
+    /// Add (`true`) or strip (`false`) a period at the end of the subject
+    #[serde(default)]
+    pub trailing_period: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HeaderFormatConfig {
+    Conventional,
+    Gitmoji,
+    Subsystem,
+    Plain,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubjectCaseConfig {
+    Lower,
+    Upper,
+}
+
+impl StyleConfig {
+    /// Build the style named `name` on top of its base preset
+    pub fn to_style(&self, name: &str) -> Result<MessageStyle> {
+        let base = self.base.as_deref().unwrap_or("conventional");
+        let mut style = MessageStyle::preset(base)
+            .ok_or_else(|| anyhow!("Unknown base style `{}` for style `{}`", base, name))?;
+        style.name = name.to_string();
+
+        if let Some(header) = self.header {
+            style.rules.header = match header {
+                HeaderFormatConfig::Conventional => HeaderFormat::Conventional,
+                HeaderFormatConfig::Gitmoji => HeaderFormat::Gitmoji,
+                HeaderFormatConfig::Subsystem => HeaderFormat::Subsystem,
+                HeaderFormatConfig::Plain => HeaderFormat::Plain,
+            };
+        }
+        if let Some(instructions) = &self.instructions {
+            style.instructions = instructions.clone();
+        }
+        if let Some(types) = &self.types {
+            style.rules.types = types.clone();
+        }
+        if let Some(max) = self.max_subject_length {
+            style.rules.max_subject_length = max;
+        }
+        if let Some(max) = self.max_body_line_length {
+            style.rules.max_body_line_length = Some(max);
+        }
+        if let Some(body) = self.body {
+            style.rules.allow_body = body;
+        }
+        if let Some(case) = self.subject_case {
+            style.subject_case = Some(match case {
+                SubjectCaseConfig::Lower => SubjectCase::Lower,
+                SubjectCaseConfig::Upper => SubjectCase::Upper,
+            });
+        }
+        if let Some(period) = self.trailing_period {
+            style.trailing_period = Some(period);
+        }
+        Ok(style)
+    }
+}
+
+/// Scope detection configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ScopeConfig {
+    /// Infer scopes from Cargo workspace members and package.json workspaces
+    #[serde(default)]
+    pub auto: Option<bool>,
+
+    /// Path glob to scope mapping, e.g. `"crates/llm/**" = "llm"`
+    #[serde(default)]
+    pub map: Option<BTreeMap<String, String>>,
+}
+
+/// Ticket reference configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TicketConfig {
+    /// Disable ticket injection while keeping the section
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Regexes matched against the branch name; a `ticket` named group selects the reference
+    #[serde(default)]
+    pub patterns: Option<Vec<String>>,
+
+    /// Where to insert the reference: `footer` (default) or `subject`
+    #[serde(default)]
+    pub placement: Option<TicketPlacementConfig>,
+
+    /// Template for the inserted text, `{ticket}` is replaced by the reference
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TicketPlacementConfig {
+    Subject,
+    Footer,
+}
+
+impl TicketConfig {
+    /// Build the ticket reference, or `None` if disabled
+    pub fn to_reference(&self) -> Result<Option<TicketReference>> {
+        if self.enabled == Some(false) {
+            return Ok(None);
+        }
+
+        let patterns = self.patterns.clone().unwrap_or_else(|| {
+            DEFAULT_TICKET_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect()
+        });
+        let (placement, default_template) = match self.placement {
+            Some(TicketPlacementConfig::Subject) => {
+                (TicketPlacement::Subject, DEFAULT_SUBJECT_TEMPLATE)
+            }
+            Some(TicketPlacementConfig::Footer) | None => {
+                (TicketPlacement::Footer, DEFAULT_FOOTER_TEMPLATE)
+            }
+        };
+        let template = self
+            .template
+            .clone()
+            .unwrap_or_else(|| default_template.to_string());
+
+        TicketReference::new(&patterns, placement, template).map(Some)
+    }
+}
+
+/// Commit trailer configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TrailerConfig {
+    /// Always add a Signed-off-by trailer
+    #[serde(default)]
+    pub sign_off: Option<bool>,
+
+    /// Co-authors added as Co-authored-by trailers ("Name <email>")
+    #[serde(default)]
+    pub co_authors: Option<Vec<String>>,
+
+    /// Arbitrary trailers ("Token: value")
+    #[serde(default)]
+    pub custom: Option<Vec<String>>,
+}
+
+/// Diff context configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ContextConfig {
+    /// Include the whole enclosing function or class of each hunk
+    #[serde(default)]
+    pub function_context: Option<bool>,
+
+    /// Include the full contents of changed files with at most this many lines
+    #[serde(default)]
+    pub full_file_lines: Option<usize>,
+
+    /// Maximum size of the diff and context in characters
+    #[serde(default)]
+    pub budget: Option<usize>,
+}
+
+/// Code review configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ReviewConfig {
+    /// Review staged changes before `commit --apply` commits them
+    #[serde(default)]
+    pub before_apply: Option<bool>,
+}
+
+/// Branch name configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct BranchConfig {
+    /// Name pattern with `{type}`, `{scope}`, `{ticket}` and `{slug}` placeholders
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+/// Token usage configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct UsageConfig {
+    /// Append the tokens of every run to the usage ledger; off unless enabled
+    #[serde(default)]
+    pub ledger: Option<bool>,
+
+    /// Team name recorded with every run, for splitting spend by team
+    #[serde(default)]
+    pub team: Option<String>,
+
+    /// Prices by model name
+    #[serde(default)]
+    pub prices: Option<BTreeMap<String, ModelPrice>>,
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+}
+
+/// Settings shared through a `.git-gen.toml` in the repository root
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct RepoConfig {
+    /// Language of commit subjects and bodies, e.g. `ja`
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl RepoConfig {
+    /// File name looked up in the repository root
+    pub const FILE_NAME: &str = ".git-gen.toml";
+
+    /// Load the repository config, or `None` if the repository has none
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(Self::FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let config = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(config))
+    }
+}
+
 impl AppConfig {
     /// Load configuration from file or environment variables
     /// Automatically creates config file if it doesn't exist
@@ -75,6 +457,21 @@ impl AppConfig {
         dirs::home_dir().map(|home_dir| home_dir.join(".git-gen").join("config.toml"))
     }
 
+    /// Get the usage ledger path, next to the config file
+    pub fn get_usage_ledger_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home_dir| home_dir.join(".git-gen").join("usage.jsonl"))
+    }
+
+    /// Get the journal of generated messages, next to the config file
+    pub fn get_history_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home_dir| home_dir.join(".git-gen").join("history.jsonl"))
+    }
+
+    /// Get the directory of cached responses, next to the config file
+    pub fn get_cache_dir() -> Option<PathBuf> {
+        dirs::home_dir().map(|home_dir| home_dir.join(".git-gen").join("cache"))
+    }
+
     /// Create config file from environment variables or with example values
     fn create_config_file(config_path: &Path) -> Result<Self> {
         // Create parent directory if it doesn't exist
@@ -99,6 +496,7 @@ impl AppConfig {
                         api_key: "your-api-key-here".to_string(),
                         model: "gemini-pro".to_string(),
                     }),
+                    ..Default::default()
                 }
             }
         };
@@ -158,11 +556,7 @@ impl AppConfig {
 
     /// Load configuration from environment variables
     fn load_from_env() -> Result<Self> {
-        let mut config = AppConfig {
-            default_provider: None,
-            openai: None,
-            gemini: None,
-        };
+        let mut config = AppConfig::default();
 
         // Try OpenAI
         if let (Ok(api_key), Ok(model)) = (env::var("OPENAI_API_KEY"), env::var("OPENAI_MODEL")) {
@@ -198,6 +592,167 @@ impl AppConfig {
         self.default_provider.as_deref()
     }
 
+    /// Get the commit message validation rules, or `None` if validation is disabled
+    pub fn commit_rules(&self, style: &MessageStyle) -> Option<CommitRules> {
+        self.validation
+            .clone()
+            .unwrap_or_default()
+            .to_rules(style.rules.clone())
+    }
+
+    /// Resolve the message style by name, or the configured one
+    ///
+    /// Styles under `[styles]` take precedence over built-in presets.
+    pub fn message_style(&self, name: Option<&str>) -> Result<MessageStyle> {
+        let name = name.or(self.style.as_deref()).unwrap_or("conventional");
+        if let Some(style) = self.styles.as_ref().and_then(|styles| styles.get(name)) {
+            return style.to_style(name);
+        }
+        MessageStyle::preset(name).ok_or_else(|| {
+            anyhow!(
+                "Unknown message style `{}`; use one of {} or define it under [styles]",
+                name,
+                PRESETS.join(", ")
+            )
+        })
+    }
+
+    /// Get the scope mappings for the repository at `root`
+    ///
+    /// Explicit mappings come first; workspace members are added in auto mode.
+    pub fn scope_mappings(&self, root: &Path) -> Result<Vec<ScopeMapping>> {
+        let Some(scopes) = &self.scopes else {
+            return Ok(Vec::new());
+        };
+
+        let mut mappings: Vec<ScopeMapping> = scopes
+            .map
+            .iter()
+            .flatten()
+            .map(|(pattern, scope)| ScopeMapping::new(pattern.as_str(), scope.as_str()))
+            .collect();
+
+        if scopes.auto.unwrap_or(false) {
+            mappings.extend(crate::workspace::detect_workspace_scopes(root)?);
+        }
+
+        Ok(mappings)
+    }
+
+    /// Get the ticket reference settings, or `None` if not configured
+    pub fn ticket_reference(&self) -> Result<Option<TicketReference>> {
+        match &self.ticket {
+            Some(ticket) => ticket.to_reference(),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether a Signed-off-by trailer should always be added
+    pub fn sign_off(&self) -> bool {
+        self.trailers
+            .as_ref()
+            .and_then(|t| t.sign_off)
+            .unwrap_or(false)
+    }
+
+    /// Get the configured co-author and custom trailers
+    pub fn trailers(&self) -> Result<Vec<Footer>> {
+        let Some(trailers) = &self.trailers else {
+            return Ok(Vec::new());
+        };
+
+        let mut result: Vec<Footer> = trailers
+            .co_authors
+            .iter()
+            .flatten()
+            .map(|author| Footer {
+                token: "Co-authored-by".to_string(),
+                value: author.clone(),
+            })
+            .collect();
+        for trailer in trailers.custom.iter().flatten() {
+            result.push(parse_trailer(trailer)?);
+        }
+        Ok(result)
+    }
+
+    /// Options for the context sent along with the diff
+    pub fn context_options(&self) -> ContextOptions {
+        let defaults = ContextOptions::default();
+        let Some(context) = &self.context else {
+            return defaults;
+        };
+        ContextOptions {
+            function_context: context
+                .function_context
+                .unwrap_or(defaults.function_context),
+            full_file_lines: context.full_file_lines.unwrap_or(defaults.full_file_lines),
+            budget: context.budget.unwrap_or(defaults.budget),
+        }
+    }
+
+    /// Whether staged changes are reviewed before `commit --apply`
+    pub fn review_before_apply(&self) -> bool {
+        self.review
+            .as_ref()
+            .and_then(|r| r.before_apply)
+            .unwrap_or(false)
+    }
+
+    /// Language for commit messages: the repository's `.git-gen.toml` wins
+    /// over the global setting
+    pub fn language(&self, root: &Path) -> Result<Option<String>> {
+        let repo_config = RepoConfig::load(root)?;
+        Ok(repo_config
+            .and_then(|c| c.language)
+            .or_else(|| self.language.clone()))
+    }
+
+    /// Pattern for generated branch names
+    pub fn branch_pattern(&self) -> String {
+        self.branch
+            .as_ref()
+            .and_then(|b| b.pattern.clone())
+            .unwrap_or_else(|| DEFAULT_BRANCH_PATTERN.to_string())
+    }
+
+    /// Whether runs are recorded in the usage ledger
+    ///
+    /// Opt-in, since the ledger keeps a record of every repository used.
+    pub fn usage_ledger(&self) -> bool {
+        self.usage.as_ref().and_then(|u| u.ledger).unwrap_or(false)
+    }
+
+    /// Team recorded with every run
+    pub fn usage_team(&self) -> Option<String> {
+        self.usage.as_ref().and_then(|u| u.team.clone())
+    }
+
+    /// Configured price of a model, if any
+    pub fn model_price(&self, model: &str) -> Option<ModelPrice> {
+        self.usage
+            .as_ref()
+            .and_then(|u| u.prices.as_ref())
+            .and_then(|prices| prices.get(model))
+            .copied()
+    }
+
+    /// Whether commitlint configs in the repository should be honored
+    pub fn use_commitlint(&self) -> bool {
+        self.validation
+            .as_ref()
+            .and_then(|v| v.commitlint)
+            .unwrap_or(true)
+    }
+
+    /// Get the number of re-prompts allowed when validation fails
+    pub fn max_retries(&self) -> usize {
+        self.validation
+            .as_ref()
+            .and_then(|v| v.max_retries)
+            .unwrap_or(git_gen_core::services::commit_service::DEFAULT_MAX_RETRIES)
+    }
+
     /// Check if a specific provider is configured
     pub fn has_provider(&self, provider: &str) -> bool {
         match provider {
@@ -208,6 +763,12 @@ impl AppConfig {
     }
 }
 
+/// Parse a `Token: value` trailer from config or the command line
+pub fn parse_trailer(trailer: &str) -> Result<Footer> {
+    parse_footer(trailer.trim())
+        .ok_or_else(|| anyhow!("Invalid trailer '{}', expected 'Token: value'", trailer))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,7 +790,7 @@ mod tests {
                 api_key: "key".to_string(),
                 model: "model".to_string(),
             }),
-            gemini: None,
+            ..Default::default()
         };
 
         assert!(config.has_provider("openai"));
@@ -240,10 +801,78 @@ mod tests {
     fn test_get_default_provider() {
         let config = AppConfig {
             default_provider: Some("openai".to_string()),
-            openai: None,
-            gemini: None,
+            ..Default::default()
         };
 
         assert_eq!(config.get_default_provider(), Some("openai"));
     }
+
+    #[test]
+    fn test_validation_rules() {
+        let config: AppConfig =
+            toml::from_str("[validation]\ntypes = [\"feat\", \"fix\"]\nmax_subject_length = 50\n")
+                .unwrap();
+        let rules = config.commit_rules(&MessageStyle::default()).unwrap();
+        assert_eq!(rules.types, vec!["feat", "fix"]);
+        assert_eq!(rules.max_subject_length, 50);
+        assert!(rules.require_imperative);
+
+        let config: AppConfig = toml::from_str("[validation]\nenabled = false\n").unwrap();
+        assert!(config.commit_rules(&MessageStyle::default()).is_none());
+    }
+
+    #[test]
+    fn test_message_style() {
+        let config: AppConfig = toml::from_str(
+            "style = \"team\"\n[styles.team]\nbase = \"gitmoji\"\nbody = false\ntypes = [\":bug:\"]\n[validation]\nmax_subject_length = 60\n",
+        )
+        .unwrap();
+        let style = config.message_style(None).unwrap();
+        assert_eq!(style.name, "team");
+        assert_eq!(style.rules.header, HeaderFormat::Gitmoji);
+        assert!(!style.rules.allow_body);
+
+        let rules = config.commit_rules(&style).unwrap();
+        assert_eq!(rules.types, vec![":bug:"]);
+        assert_eq!(rules.max_subject_length, 60);
+        assert!(!rules.allow_body);
+
+        assert_eq!(
+            config.message_style(Some("kernel")).unwrap().rules.header,
+            HeaderFormat::Subsystem
+        );
+        assert!(config.message_style(Some("nope")).is_err());
+    }
+
+    #[test]
+    fn test_trailers() {
+        let config: AppConfig = toml::from_str(
+            "[trailers]\nsign_off = true\nco_authors = [\"Jane <jane@example.com>\"]\ncustom = [\"Reviewed-by: Bob\"]\n",
+        )
+        .unwrap();
+        assert!(config.sign_off());
+
+        let trailers = config.trailers().unwrap();
+        assert_eq!(trailers[0].token, "Co-authored-by");
+        assert_eq!(trailers[0].value, "Jane <jane@example.com>");
+        assert_eq!(trailers[1].token, "Reviewed-by");
+        assert_eq!(trailers[1].value, "Bob");
+
+        assert!(parse_trailer("not a trailer").is_err());
+    }
+
+    #[test]
+    fn test_ticket_reference() {
+        let config: AppConfig =
+            toml::from_str("[ticket]\nplacement = \"subject\"\ntemplate = \"[{ticket}]\"\n")
+                .unwrap();
+        let ticket = config.ticket_reference().unwrap().unwrap();
+        let reference = ticket.extract("feature/PROJ-7-login").unwrap();
+        assert_eq!(
+            ticket.apply("feat: add login", &reference, HeaderFormat::Conventional),
+            "feat: [PROJ-7] add login"
+        );
+
+        assert!(AppConfig::default().ticket_reference().unwrap().is_none());
+    }
 }
@@ -0,0 +1,343 @@
+use anyhow::Context;
+use git_gen_core::GitRepository;
+use git_gen_git::GitRepositoryImpl;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+use crate::jsonl;
+use crate::ledger::{now, utc_date};
+
+/// Oldest entries are dropped beyond this many
+const MAX_ENTRIES: usize = 1000;
+
+/// Commands whose messages are commit messages and can be reused
+pub const COMMIT_COMMANDS: &[&str] = &["commit", "squash", "split"];
+
+/// What happened to a generated message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageStatus {
+    /// Shown but not committed
+    Generated,
+    /// Committed as generated
+    Accepted,
+    /// Committed after the message was changed, e.g. in the editor or by a hook
+    Edited,
+    /// Declined at the confirmation prompt
+    Rejected,
+    /// The commit was attempted and failed, e.g. because a hook rejected it
+    Failed,
+}
+
+impl MessageStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Generated => "generated",
+            Self::Accepted => "accepted",
+            Self::Edited => "edited",
+            Self::Rejected => "rejected",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// A generated message as kept in the journal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub timestamp: u64,
+    /// UTC day of the generation, `YYYY-MM-DD`
+    pub date: String,
+    pub command: String,
+    /// Repository root, if the command ran inside one
+    pub repo: Option<String>,
+    /// Commit HEAD pointed to when the message was generated
+    pub head: Option<String>,
+    pub provider: String,
+    pub model: String,
+    pub message: String,
+    pub status: MessageStatus,
+}
+
+impl JournalEntry {
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// A line of the journal
+///
+/// Status changes are appended rather than rewriting the entry; the last one
+/// for an id wins.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Record {
+    Entry(JournalEntry),
+    Status { id: u64, status: MessageStatus },
+}
+
+/// Journal of generated messages, stored as JSON lines
+///
+/// Writers append under a lock on a sibling `.lock` file, so concurrent runs
+/// neither lose entries nor hand out the same id.
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The journal next to the config file
+    pub fn open() -> Option<Self> {
+        AppConfig::get_history_path().map(Self::new)
+    }
+
+    /// All entries, oldest first; a missing journal has none
+    ///
+    /// Lines that cannot be parsed, e.g. after an interrupted write, are
+    /// skipped with a warning.
+    pub fn load(&self) -> anyhow::Result<Vec<JournalEntry>> {
+        let (entries, invalid) = parse_records(&self.read()?);
+        for (line, e) in invalid {
+            jsonl::warn_invalid(&self.path, line, &e);
+        }
+        Ok(entries)
+    }
+
+    /// Find an entry by id
+    pub fn get(&self, id: u64) -> anyhow::Result<Option<JournalEntry>> {
+        Ok(self.load()?.into_iter().find(|e| e.id == id))
+    }
+
+    /// Add an entry, assigning the next id
+    pub fn record(&self, mut entry: JournalEntry) -> anyhow::Result<u64> {
+        let _lock = self.lock()?;
+        let content = self.read()?;
+        let (entries, _) = parse_records(&content);
+        entry.id = entries.iter().map(|e| e.id).max().unwrap_or(0) + 1;
+        let id = entry.id;
+
+        // Compact once the appended lines grow well past the limit
+        if content.lines().count() >= 2 * MAX_ENTRIES {
+            let mut entries = entries;
+            entries.push(entry);
+            entries.drain(..entries.len().saturating_sub(MAX_ENTRIES));
+            write_entries(&self.path, &entries)?;
+        } else {
+            self.append(&Record::Entry(entry))?;
+        }
+        Ok(id)
+    }
+
+    /// Change the status of an entry
+    pub fn set_status(&self, id: u64, status: MessageStatus) -> anyhow::Result<()> {
+        let _lock = self.lock()?;
+        self.append(&Record::Status { id, status })
+    }
+
+    fn read(&self) -> anyhow::Result<String> {
+        jsonl::read(&self.path)
+    }
+
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        jsonl::append(&self.path, &[serde_json::to_string(record)?])
+    }
+
+    /// Hold the write lock until the returned file is dropped
+    fn lock(&self) -> anyhow::Result<File> {
+        jsonl::lock(&self.path)
+    }
+}
+
+/// Entries with their latest status, and the line numbers and errors of invalid lines
+fn parse_records(content: &str) -> (Vec<JournalEntry>, Vec<(usize, serde_json::Error)>) {
+    let mut entries: Vec<JournalEntry> = Vec::new();
+    let mut invalid = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(Record::Entry(entry)) => entries.push(entry),
+            Ok(Record::Status { id, status }) => {
+                if let Some(entry) = entries.iter_mut().rev().find(|e| e.id == id) {
+                    entry.status = status;
+                }
+            }
+            Err(e) => invalid.push((index + 1, e)),
+        }
+    }
+    (entries, invalid)
+}
+
+fn write_entries(path: &Path, entries: &[JournalEntry]) -> anyhow::Result<()> {
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&serde_json::to_string(entry)?);
+        text.push('\n');
+    }
+
+    // Write a sibling file first so an interrupted run cannot truncate the journal
+    let temporary = path.with_extension("jsonl.tmp");
+    fs::write(&temporary, text)
+        .with_context(|| format!("Failed to write {}", temporary.display()))?;
+    fs::rename(&temporary, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Record a freshly generated message, returning its id
+///
+/// The journal is a safety net, so failures only warn.
+pub async fn record_generated(
+    command: &str,
+    provider: &str,
+    model: &str,
+    message: &str,
+) -> Option<u64> {
+    let journal = Journal::open()?;
+    let git_repository = GitRepositoryImpl::new();
+    let repo = git_repository
+        .root_dir()
+        .await
+        .ok()
+        .map(|root| root.display().to_string());
+    let head = git_repository
+        .log("HEAD^!")
+        .await
+        .ok()
+        .and_then(|commits| commits.into_iter().next())
+        .map(|commit| commit.hash);
+    let timestamp = now();
+
+    let entry = JournalEntry {
+        id: 0,
+        timestamp,
+        date: utc_date(timestamp),
+        command: command.to_string(),
+        repo,
+        head,
+        provider: provider.to_string(),
+        model: model.to_string(),
+        message: message.to_string(),
+        status: MessageStatus::Generated,
+    };
+    match journal.record(entry) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            eprintln!(
+                "Warning: Failed to record the message in the history: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Update the status of a recorded message, if it was recorded
+pub fn update_status(id: Option<u64>, status: MessageStatus) {
+    if let (Some(id), Some(journal)) = (id, Journal::open())
+        && let Err(e) = journal.set_status(id, status)
+    {
+        eprintln!("Warning: Failed to update the history: {}", e);
+    }
+}
+
+/// Whether the commit just created kept `message` or was changed on the way
+pub async fn committed_status(message: &str) -> MessageStatus {
+    let head = GitRepositoryImpl::new()
+        .log("HEAD^!")
+        .await
+        .ok()
+        .and_then(|commits| commits.into_iter().next());
+    match head {
+        Some(commit) if !same_message(&commit.subject, &commit.body, message) => {
+            MessageStatus::Edited
+        }
+        _ => MessageStatus::Accepted,
+    }
+}
+
+/// Compare ignoring whitespace at the ends of lines and of the message
+fn same_message(subject: &str, body: &str, message: &str) -> bool {
+    let normalize = |text: &str| {
+        text.trim()
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let committed = if body.trim().is_empty() {
+        subject.to_string()
+    } else {
+        format!("{}\n\n{}", subject, body)
+    };
+    normalize(&committed) == normalize(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    fn entry(message: &str) -> JournalEntry {
+        JournalEntry {
+            id: 0,
+            timestamp: 0,
+            date: "2026-10-18".to_string(),
+            command: "commit".to_string(),
+            repo: None,
+            head: None,
+            provider: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            message: message.to_string(),
+            status: MessageStatus::Generated,
+        }
+    }
+
+    #[test]
+    fn test_record_and_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path().join("history.jsonl"));
+
+        assert_eq!(journal.record(entry("feat: one")).unwrap(), 1);
+        assert_eq!(journal.record(entry("fix: two\n\nBody")).unwrap(), 2);
+        journal.set_status(2, MessageStatus::Failed).unwrap();
+
+        let entries = journal.load().unwrap();
+        let second = journal.get(2).unwrap().unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].status, MessageStatus::Generated);
+        assert_eq!(second.status, MessageStatus::Failed);
+        assert_eq!(second.subject(), "fix: two");
+    }
+
+    #[test]
+    fn test_skips_invalid_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let journal = Journal::new(path.clone());
+
+        journal.record(entry("feat: one")).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"id\": 2, \"mess").unwrap();
+        assert_eq!(journal.record(entry("fix: two")).unwrap(), 2);
+        journal.set_status(2, MessageStatus::Accepted).unwrap();
+
+        let entries = journal.load().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].subject(), "fix: two");
+        assert_eq!(entries[1].status, MessageStatus::Accepted);
+    }
+
+    #[test]
+    fn test_same_message() {
+        assert!(same_message("feat: add", "", "feat: add\n"));
+        assert!(same_message("feat: add", "Body  \n", "feat: add\n\nBody"));
+        assert!(!same_message("feat: add login", "", "feat: add"));
+    }
+}
@@ -0,0 +1,72 @@
+//! JSON lines files that several runs append to, such as the history and the usage ledger
+
+use anyhow::Context;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Contents of the file; a missing file is empty
+pub fn read(path: &Path) -> anyhow::Result<String> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    // Invalid UTF-8 only spoils the lines it is on
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Hold the write lock of `path` until the returned file is dropped
+///
+/// The lock is a sibling `.lock` file, so it survives the file being replaced.
+pub fn lock(path: &Path) -> anyhow::Result<File> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let path = path.with_extension("jsonl.lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.lock()
+        .with_context(|| format!("Failed to lock {}", path.display()))?;
+    Ok(file)
+}
+
+/// Append lines, starting on a fresh line after an interrupted write
+pub fn append(path: &Path, lines: &[String]) -> anyhow::Result<()> {
+    let write = || -> std::io::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        let mut text = String::new();
+        if file.metadata()?.len() > 0 {
+            let mut last = [0];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                text.push('\n');
+            }
+        }
+        for line in lines {
+            text.push_str(line);
+            text.push('\n');
+        }
+        file.write_all(text.as_bytes())
+    };
+    write().with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Warn about a line that cannot be parsed and is skipped
+pub fn warn_invalid(path: &Path, line: usize, error: &serde_json::Error) {
+    eprintln!(
+        "Warning: Skipping invalid entry on line {} of {}: {}",
+        line,
+        path.display(),
+        error
+    );
+}
@@ -0,0 +1,237 @@
+use git_gen_core::{GitRepository, LlmGenerater, TokenUsage};
+use git_gen_git::GitRepositoryImpl;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::{AppConfig, ModelPrice};
+use crate::jsonl;
+
+struct Tracked {
+    provider: String,
+    model: String,
+    llm: Arc<dyn LlmGenerater>,
+}
+
+/// One run of a command with one model, as recorded in the usage ledger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// UTC day of the run, `YYYY-MM-DD`
+    pub date: String,
+    pub timestamp: u64,
+    pub command: String,
+    /// Repository root, if the command ran inside one
+    pub repo: Option<String>,
+    pub team: Option<String>,
+    pub provider: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    /// Estimated cost in USD, if the model has a configured price
+    pub cost: Option<f64>,
+}
+
+/// Estimated cost in USD
+pub fn cost(usage: TokenUsage, price: ModelPrice) -> f64 {
+    (usage.prompt_tokens as f64 * price.input + usage.completion_tokens as f64 * price.output)
+        / 1_000_000.0
+}
+
+/// Generaters created by a command, read back once the command finishes
+///
+/// Created once per run and passed to the command, which registers every
+/// generater it creates.
+#[derive(Default)]
+pub struct UsageTracker {
+    tracked: Mutex<Vec<Tracked>>,
+}
+
+impl UsageTracker {
+    /// Remember a generater so its usage is reported when the command finishes
+    pub fn track(&self, provider: &str, model: &str, llm: Arc<dyn LlmGenerater>) {
+        self.tracked.lock().unwrap().push(Tracked {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            llm,
+        });
+    }
+
+    /// Report the tokens used by this run and append them to the ledger
+    ///
+    /// Failing to write the ledger only warns, since the command itself succeeded.
+    pub async fn finish(&self, command: &str, verbose: bool, config: &AppConfig) {
+        let tracked = std::mem::take(&mut *self.tracked.lock().unwrap());
+
+        // Commands may create several generaters for the same model
+        let mut totals: Vec<(String, String, TokenUsage)> = Vec::new();
+        for t in tracked {
+            let usage = t.llm.usage();
+            match totals
+                .iter_mut()
+                .find(|(provider, model, _)| *provider == t.provider && *model == t.model)
+            {
+                Some((_, _, total)) => *total += usage,
+                None => totals.push((t.provider, t.model, usage)),
+            }
+        }
+        totals.retain(|(_, _, usage)| !usage.is_empty());
+        if totals.is_empty() {
+            return;
+        }
+
+        let timestamp = now();
+        let repo = GitRepositoryImpl::new()
+            .root_dir()
+            .await
+            .ok()
+            .map(|root| root.display().to_string());
+
+        let entries: Vec<LedgerEntry> = totals
+            .into_iter()
+            .map(|(provider, model, usage)| LedgerEntry {
+                date: utc_date(timestamp),
+                timestamp,
+                command: command.to_string(),
+                repo: repo.clone(),
+                team: config.usage_team(),
+                cost: config.model_price(&model).map(|price| cost(usage, price)),
+                provider,
+                model,
+                usage,
+            })
+            .collect();
+
+        if verbose {
+            for entry in &entries {
+                let cost = entry
+                    .cost
+                    .map(|cost| format!(", estimated cost ${:.4}", cost))
+                    .unwrap_or_default();
+                eprintln!(
+                    "ℹ {}: {} prompt + {} completion tokens{}",
+                    entry.model, entry.usage.prompt_tokens, entry.usage.completion_tokens, cost
+                );
+            }
+        }
+
+        if config.usage_ledger()
+            && let Some(path) = AppConfig::get_usage_ledger_path()
+            && let Err(e) = append(&path, &entries)
+        {
+            eprintln!("Warning: Failed to write usage ledger: {}", e);
+        }
+    }
+}
+
+/// Append entries to the ledger as JSON lines
+fn append(path: &Path, entries: &[LedgerEntry]) -> anyhow::Result<()> {
+    let lines = entries
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    let _lock = jsonl::lock(path)?;
+    jsonl::append(path, &lines)
+}
+
+/// Read all entries of the ledger; a missing ledger has none
+///
+/// Lines that cannot be parsed, e.g. after an interrupted write, are skipped
+/// with a warning.
+pub fn load(path: &Path) -> anyhow::Result<Vec<LedgerEntry>> {
+    let mut entries = Vec::new();
+    for (index, line) in jsonl::read(path)?.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => jsonl::warn_invalid(path, index + 1, &e),
+        }
+    }
+    Ok(entries)
+}
+
+/// Current Unix timestamp in seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// `YYYY-MM-DD` of a Unix timestamp in UTC
+pub fn utc_date(timestamp: u64) -> String {
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_date() {
+        assert_eq!(utc_date(0), "1970-01-01");
+        assert_eq!(utc_date(951_782_400), "2000-02-29");
+        assert_eq!(utc_date(1_792_281_599), "2026-10-17");
+    }
+
+    #[test]
+    fn test_cost() {
+        let usage = TokenUsage {
+            prompt_tokens: 2_000_000,
+            completion_tokens: 500_000,
+        };
+        let price = ModelPrice {
+            input: 0.15,
+            output: 0.6,
+        };
+        assert!((cost(usage, price) - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_skips_torn_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("usage.jsonl");
+        let entry = LedgerEntry {
+            date: "2026-10-18".to_string(),
+            timestamp: 0,
+            command: "commit".to_string(),
+            repo: None,
+            team: None,
+            provider: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            usage: TokenUsage {
+                prompt_tokens: 100,
+                completion_tokens: 10,
+            },
+            cost: None,
+        };
+
+        append(&path, std::slice::from_ref(&entry)).unwrap();
+        // A run that crashed mid-append
+        std::fs::write(
+            &path,
+            format!("{}{{\"date\": \"2026", jsonl::read(&path).unwrap()),
+        )
+        .unwrap();
+        append(&path, std::slice::from_ref(&entry)).unwrap();
+
+        assert_eq!(load(&path).unwrap(), vec![entry.clone(), entry]);
+    }
+}
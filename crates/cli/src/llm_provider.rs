@@ -1,8 +1,10 @@
 use anyhow::anyhow;
 use git_gen_core::LlmGenerater;
 use git_gen_llm::{GeminiLlmGenerater, OpenAILlmGenerater};
+use std::sync::Arc;
 
 use crate::config::AppConfig;
+use crate::ledger::UsageTracker;
 
 /// LLM provider type
 #[derive(Clone, Debug, clap::ValueEnum)]
@@ -12,6 +14,44 @@ pub enum LlmProvider {
 }
 
 impl LlmProvider {
+    /// Name of the provider's section in the config
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OpenAI => "openai",
+            Self::Gemini => "gemini",
+        }
+    }
+
+    /// Configured model of this provider
+    pub fn model<'a>(&self, config: &'a AppConfig) -> Option<&'a str> {
+        match self {
+            Self::OpenAI => config.openai.as_ref().map(|c| c.model.as_str()),
+            Self::Gemini => config.gemini.as_ref().map(|c| c.model.as_str()),
+        }
+    }
+
+    /// Use the provider given on the command line, or detect one from config
+    pub fn resolve(provider: Option<Self>, config: &AppConfig) -> anyhow::Result<Self> {
+        match provider {
+            Some(p) => {
+                // Validate that the specified provider is configured
+                let provider_name = p.name();
+                if !config.has_provider(provider_name) {
+                    return Err(anyhow!(
+                        "{} is not configured in config file or environment variables",
+                        provider_name
+                    ));
+                }
+                Ok(p)
+            }
+            None => Self::detect_from_config(config).ok_or_else(|| {
+                anyhow!(
+                    "No LLM provider configured. Please configure a provider in config file or environment variables, or use --llm flag to specify provider."
+                )
+            }),
+        }
+    }
+
     /// Detect LLM provider from config
     pub fn detect_from_config(config: &AppConfig) -> Option<Self> {
         // Check default provider first
@@ -35,9 +75,13 @@ impl LlmProvider {
     }
 
     /// Create LLM generater from this provider using config
+    ///
+    /// The generater is registered with `usage` so its tokens are reported
+    /// when the command finishes.
     pub fn create_llm_generater_from_config(
         &self,
         config: &AppConfig,
+        usage: &UsageTracker,
     ) -> anyhow::Result<Box<dyn LlmGenerater>> {
         match self {
             Self::OpenAI => {
@@ -45,10 +89,11 @@ impl LlmProvider {
                     anyhow!("OpenAI is not configured in config file or environment")
                 })?;
 
-                let llm = OpenAILlmGenerater::with_config(
+                let llm = Arc::new(OpenAILlmGenerater::with_config(
                     openai_config.api_key.clone(),
                     openai_config.model.clone(),
-                );
+                ));
+                usage.track(self.name(), &openai_config.model, llm.clone());
                 Ok(Box::new(llm))
             }
             Self::Gemini => {
@@ -56,10 +101,11 @@ impl LlmProvider {
                     anyhow!("Gemini is not configured in config file or environment")
                 })?;
 
-                let llm = GeminiLlmGenerater::with_config(
+                let llm = Arc::new(GeminiLlmGenerater::with_config(
                     gemini_config.api_key.clone(),
                     gemini_config.model.clone(),
-                );
+                ));
+                usage.track(self.name(), &gemini_config.model, llm.clone());
                 Ok(Box::new(llm))
             }
         }
@@ -1,11 +1,20 @@
 mod commands;
+mod commitlint;
 mod config;
+mod journal;
+mod jsonl;
+mod ledger;
 mod llm_provider;
+mod response_cache;
+mod workspace;
 
 use config::AppConfig;
+use ledger::UsageTracker;
 
 use clap::{Parser, Subcommand};
-use commands::commit;
+use commands::{
+    branch, bump, cache, changelog, commit, explain, history, pr, review, split, squash, usage,
+};
 
 #[derive(Parser)]
 #[command(
@@ -16,6 +25,10 @@ use commands::commit;
     long_about = None
 )]
 struct Cli {
+    /// Report token usage and estimated cost after the command
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -23,15 +36,49 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Generate commit message from staged changes
-    Commit {
-        /// Apply the commit after generating the message
-        #[arg(short, long)]
-        apply: bool,
-
-        /// LLM provider to use (openai or gemini)
-        #[arg(short, long, value_enum)]
-        llm: Option<llm_provider::LlmProvider>,
-    },
+    Commit(commit::CommitArgs),
+    /// Split staged changes into several focused commits
+    Split(split::SplitArgs),
+    /// Generate one message that condenses a range of commits
+    Squash(squash::SquashArgs),
+    /// Generate a pull request title and description for the current branch
+    Pr(pr::PrArgs),
+    /// Write release notes for a range of commits into CHANGELOG.md
+    Changelog(changelog::ChangelogArgs),
+    /// Recommend the next semantic version and optionally tag it
+    Bump(bump::BumpArgs),
+    /// Review staged changes for bugs, leftovers and risky changes
+    Review(review::ReviewArgs),
+    /// Explain what an existing commit or range did, in plain language
+    Explain(explain::ExplainArgs),
+    /// Propose a branch name from staged changes or a task description
+    Branch(branch::BranchArgs),
+    /// Summarize recorded token usage and cost
+    Usage(usage::UsageArgs),
+    /// Manage cached commit messages
+    Cache(cache::CacheArgs),
+    /// Browse and reuse previously generated messages
+    History(history::HistoryArgs),
+}
+
+impl Commands {
+    /// Name recorded in the usage ledger
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Commit(_) => "commit",
+            Self::Split(_) => "split",
+            Self::Squash(_) => "squash",
+            Self::Pr(_) => "pr",
+            Self::Changelog(_) => "changelog",
+            Self::Bump(_) => "bump",
+            Self::Review(_) => "review",
+            Self::Explain(_) => "explain",
+            Self::Branch(_) => "branch",
+            Self::Usage(_) => "usage",
+            Self::Cache(_) => "cache",
+            Self::History(_) => "history",
+        }
+    }
 }
 
 #[tokio::main]
@@ -46,10 +93,29 @@ async fn main() {
     };
 
     let cli = Cli::parse();
+    let command = cli.command.name();
+    let usage_config = config.clone();
+    let usage = UsageTracker::default();
+
+    let result = match cli.command {
+        Commands::Commit(args) => commit::run(args, config, &usage).await,
+        Commands::Split(args) => split::run(args, config, &usage).await,
+        Commands::Squash(args) => squash::run(args, config, &usage).await,
+        Commands::Pr(args) => pr::run(args, config, &usage).await,
+        Commands::Changelog(args) => changelog::run(args, config, &usage).await,
+        Commands::Bump(args) => bump::run(args, config, &usage).await,
+        Commands::Review(args) => review::run(args, config, &usage).await,
+        Commands::Explain(args) => explain::run(args, config, &usage).await,
+        Commands::Branch(args) => branch::run(args, config, &usage).await,
+        Commands::Usage(args) => usage::run(args, config).await,
+        Commands::Cache(args) => cache::run(args, config).await,
+        Commands::History(args) => history::run(args, config).await,
+    };
+
+    // Failed runs may still have used tokens
+    usage.finish(command, cli.verbose, &usage_config).await;
 
-    if let Err(e) = match cli.command {
-        Commands::Commit { apply, llm } => commit::run(llm, apply, config).await,
-    } {
+    if let Err(e) = result {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     }
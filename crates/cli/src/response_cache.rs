@@ -0,0 +1,185 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use git_gen_core::{GenerationRequest, LlmGenerater, RequestPreview, TokenUsage};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bumped when the cached format or the key material changes
+const CACHE_VERSION: &str = "1";
+
+/// Generater that answers repeated commit message requests from disk
+///
+/// The key covers the provider and the full request body, which holds the
+/// model, the system prompt and the diff, so a new prompt or model misses.
+pub struct CachedLlmGenerater {
+    inner: Box<dyn LlmGenerater>,
+    provider: String,
+    dir: PathBuf,
+}
+
+impl CachedLlmGenerater {
+    pub fn new(inner: Box<dyn LlmGenerater>, provider: &str, dir: PathBuf) -> Self {
+        Self {
+            inner,
+            provider: provider.to_string(),
+            dir,
+        }
+    }
+
+    fn path(&self, request: &GenerationRequest) -> PathBuf {
+        let mut request = request.clone();
+        request.diff = normalize_diff(&request.diff);
+        let preview = self.inner.preview_commit_message(request);
+
+        let mut hasher = Sha256::new();
+        for part in [
+            CACHE_VERSION,
+            env!("CARGO_PKG_VERSION"),
+            &self.provider,
+            &preview.body.to_string(),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let key: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        self.dir.join(format!("{}.txt", key))
+    }
+}
+
+#[async_trait]
+impl LlmGenerater for CachedLlmGenerater {
+    async fn generate_commit_message(&self, request: GenerationRequest) -> anyhow::Result<String> {
+        let path = self.path(&request);
+        if let Ok(message) = fs::read_to_string(&path) {
+            eprintln!("ℹ Reusing a cached response; pass --no-cache to generate a new one");
+            return Ok(message);
+        }
+
+        let message = self.inner.generate_commit_message(request).await?;
+        // A cache that cannot be written only costs the next run a request
+        if let Err(e) = write(&path, &message) {
+            eprintln!("Warning: Failed to cache the response: {}", e);
+        }
+        Ok(message)
+    }
+
+    async fn complete(&self, system_prompt: &str, user_message: String) -> anyhow::Result<String> {
+        self.inner.complete(system_prompt, user_message).await
+    }
+
+    fn preview_commit_message(&self, request: GenerationRequest) -> RequestPreview {
+        self.inner.preview_commit_message(request)
+    }
+
+    fn preview(&self, system_prompt: &str, user_message: String) -> RequestPreview {
+        self.inner.preview(system_prompt, user_message)
+    }
+
+    fn usage(&self) -> TokenUsage {
+        self.inner.usage()
+    }
+}
+
+/// Line endings and trailing blank lines do not change what the diff says
+fn normalize_diff(diff: &str) -> String {
+    diff.replace("\r\n", "\n").trim_end().to_string()
+}
+
+fn write(path: &Path, message: &str) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, message).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Remove all cached responses, returning how many there were
+pub fn clear(dir: &Path) -> anyhow::Result<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let count = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .count();
+    fs::remove_dir_all(dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Returns a numbered message per call and echoes the request in the preview
+    #[derive(Default)]
+    struct CountingLlm(Mutex<usize>);
+
+    #[async_trait]
+    impl LlmGenerater for CountingLlm {
+        async fn generate_commit_message(
+            &self,
+            _request: GenerationRequest,
+        ) -> anyhow::Result<String> {
+            let mut calls = self.0.lock().unwrap();
+            *calls += 1;
+            Ok(format!("feat: message {}", calls))
+        }
+
+        async fn complete(
+            &self,
+            _system_prompt: &str,
+            _user_message: String,
+        ) -> anyhow::Result<String> {
+            unreachable!()
+        }
+
+        fn preview_commit_message(&self, request: GenerationRequest) -> RequestPreview {
+            RequestPreview {
+                body: serde_json::json!({
+                    "diff": request.diff,
+                    "instructions": request.instructions,
+                }),
+                ..Default::default()
+            }
+        }
+
+        fn preview(&self, _system_prompt: &str, _user_message: String) -> RequestPreview {
+            RequestPreview::default()
+        }
+
+        fn usage(&self) -> TokenUsage {
+            TokenUsage::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reuses_cached_response() {
+        let scratch = tempfile::tempdir().unwrap();
+        let dir = scratch.path().join("cache");
+        let llm = CachedLlmGenerater::new(Box::new(CountingLlm::default()), "openai", dir.clone());
+
+        let first = llm
+            .generate_commit_message(GenerationRequest::new("+a\n".to_string()))
+            .await
+            .unwrap();
+        let again = llm
+            .generate_commit_message(GenerationRequest::new("+a\r\n\n".to_string()))
+            .await
+            .unwrap();
+        let other = llm
+            .generate_commit_message(GenerationRequest::new("+b\n".to_string()))
+            .await
+            .unwrap();
+        let cleared = clear(&dir).unwrap();
+
+        assert_eq!(first, "feat: message 1");
+        assert_eq!(again, first);
+        assert_eq!(other, "feat: message 2");
+        assert_eq!(cleared, 2);
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::{Context, Result};
+use git_gen_core::ScopeMapping;
+use git_gen_core::message::scope::glob_match;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Derive scope mappings from Cargo workspace members and package.json workspaces
+///
+/// Each member directory maps to a scope named after the directory, so
+/// `crates/llm` becomes `crates/llm/** -> llm`.
+pub fn detect_workspace_scopes(root: &Path) -> Result<Vec<ScopeMapping>> {
+    let mut members = cargo_members(root)?;
+    members.extend(npm_workspaces(root)?);
+
+    let mut mappings = Vec::new();
+    for pattern in members {
+        for dir in expand_member(root, &pattern) {
+            let Some(name) = dir.rsplit('/').next() else {
+                continue;
+            };
+            let mapping = ScopeMapping::new(format!("{}/**", dir), name);
+            if !mappings.contains(&mapping) {
+                mappings.push(mapping);
+            }
+        }
+    }
+    Ok(mappings)
+}
+
+/// Read `[workspace] members` from the root Cargo.toml
+fn cargo_members(root: &Path) -> Result<Vec<String>> {
+    let path = root.join("Cargo.toml");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let manifest: toml::Table =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    Ok(manifest
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|m| m.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Read `workspaces` from the root package.json (array or `{ packages: [...] }`)
+fn npm_workspaces(root: &Path) -> Result<Vec<String>> {
+    let path = root.join("package.json");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let package: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let workspaces = match package.get("workspaces") {
+        Some(Value::Object(obj)) => obj.get("packages"),
+        other => other,
+    };
+
+    Ok(workspaces
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                // Negated patterns exclude packages; they never name a scope
+                .filter(|p| !p.starts_with('!'))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Expand a member pattern such as `crates/*` into existing directories
+fn expand_member(root: &Path, pattern: &str) -> Vec<String> {
+    let mut current = vec![String::new()];
+
+    for segment in pattern.trim_matches('/').split('/') {
+        let mut next = Vec::new();
+        for prefix in &current {
+            if segment.contains(['*', '?']) {
+                let Ok(entries) = fs::read_dir(root.join(prefix)) else {
+                    continue;
+                };
+                let mut names: Vec<String> = entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_dir())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|name| !name.starts_with('.') && glob_match(segment, name))
+                    .collect();
+                names.sort();
+                next.extend(names.into_iter().map(|name| join(prefix, &name)));
+            } else {
+                next.push(join(prefix, segment));
+            }
+        }
+        current = next;
+    }
+
+    current
+        .into_iter()
+        .filter(|dir| !dir.is_empty() && root.join(dir).is_dir())
+        .collect()
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_workspace_scopes() {
+        let root = std::env::temp_dir().join(format!("git-gen-workspace-{}", std::process::id()));
+        fs::create_dir_all(root.join("crates/core")).unwrap();
+        fs::create_dir_all(root.join("crates/llm")).unwrap();
+        fs::create_dir_all(root.join("packages/web")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{ "workspaces": { "packages": ["packages/*", "!packages/legacy"] } }"#,
+        )
+        .unwrap();
+
+        let mappings = detect_workspace_scopes(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            mappings,
+            vec![
+                ScopeMapping::new("crates/core/**", "core"),
+                ScopeMapping::new("crates/llm/**", "llm"),
+                ScopeMapping::new("packages/web/**", "web"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+/// Default branch name pattern
+pub const DEFAULT_BRANCH_PATTERN: &str = "{type}/{ticket}-{slug}";
+
+/// Maximum length of the slug derived from the subject
+pub const MAX_SLUG_LENGTH: usize = 40;
+
+/// Characters that separate the parts of a branch name
+const SEPARATORS: [char; 4] = ['/', '-', '_', '.'];
+
+/// Values substituted into a branch name pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchParts {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub ticket: Option<String>,
+    pub slug: String,
+}
+
+/// Fill in `{type}`, `{scope}`, `{ticket}` and `{slug}` in a pattern
+///
+/// Placeholders without a value are dropped together with the separator next
+/// to them, so `{type}/{ticket}-{slug}` becomes `feat/add-login` without a
+/// ticket.
+pub fn render_branch(pattern: &str, parts: &BranchParts) -> String {
+    let ticket = parts
+        .ticket
+        .as_deref()
+        .map(|t| t.trim_start_matches('#'))
+        .unwrap_or_default();
+    let name = pattern
+        .replace("{type}", &slugify(&parts.commit_type, MAX_SLUG_LENGTH))
+        .replace(
+            "{scope}",
+            &slugify(parts.scope.as_deref().unwrap_or_default(), MAX_SLUG_LENGTH),
+        )
+        .replace("{ticket}", ticket)
+        .replace("{slug}", &parts.slug);
+
+    // Collapse the separator runs left behind by empty placeholders
+    let mut result = String::new();
+    for c in name.chars() {
+        if SEPARATORS.contains(&c) && result.ends_with(SEPARATORS) {
+            continue;
+        }
+        result.push(c);
+    }
+    result.trim_matches(SEPARATORS).to_string()
+}
+
+/// Lowercase ASCII words joined by `-`, cut at a word boundary
+pub fn slugify(text: &str, max_length: usize) -> String {
+    let mut slug = String::new();
+    for word in text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let extra = if slug.is_empty() { 0 } else { 1 };
+        if !slug.is_empty() && slug.len() + extra + word.len() > max_length {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug.truncate(max_length);
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(scope: Option<&str>, ticket: Option<&str>) -> BranchParts {
+        BranchParts {
+            commit_type: "feat".to_string(),
+            scope: scope.map(str::to_string),
+            ticket: ticket.map(str::to_string),
+            slug: "add-login".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_branch() {
+        assert_eq!(
+            render_branch(DEFAULT_BRANCH_PATTERN, &parts(None, Some("PROJ-12"))),
+            "feat/PROJ-12-add-login"
+        );
+        assert_eq!(
+            render_branch(DEFAULT_BRANCH_PATTERN, &parts(None, None)),
+            "feat/add-login"
+        );
+        assert_eq!(
+            render_branch("{ticket}/{scope}/{slug}", &parts(Some("auth"), Some("#42"))),
+            "42/auth/add-login"
+        );
+        assert_eq!(
+            render_branch("{ticket}/{scope}/{slug}", &parts(None, None)),
+            "add-login"
+        );
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(
+            slugify("Add OAuth2 login (GitHub)!", 40),
+            "add-oauth2-login-github"
+        );
+        assert_eq!(slugify("Fix: handle   empty input", 16), "fix-handle-empty");
+        assert_eq!(slugify("averyveryverylongword", 8), "averyver");
+    }
+}
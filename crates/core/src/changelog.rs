@@ -0,0 +1,217 @@
+use crate::interfaces::git_repository::CommitInfo;
+use crate::message::parser::parse;
+
+/// Heading and introduction of a new Keep a Changelog file
+pub const CHANGELOG_HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+/// Name of the section collecting changes that are not released yet
+pub const UNRELEASED: &str = "Unreleased";
+
+/// Keep a Changelog change categories, in the order they are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeCategory {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
+
+impl ChangeCategory {
+    pub const ALL: [Self; 6] = [
+        Self::Added,
+        Self::Changed,
+        Self::Deprecated,
+        Self::Removed,
+        Self::Fixed,
+        Self::Security,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "Added",
+            Self::Changed => "Changed",
+            Self::Deprecated => "Deprecated",
+            Self::Removed => "Removed",
+            Self::Fixed => "Fixed",
+            Self::Security => "Security",
+        }
+    }
+}
+
+/// Where a commit belongs in the changelog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    Category(ChangeCategory),
+    /// Not user-facing, e.g. `chore`, `ci` or `test`
+    Internal,
+    /// A `revert` commit; whether it matters depends on what it undoes
+    Revert,
+    /// Not a Conventional Commit, or an unknown type; left to the model
+    Unknown,
+}
+
+/// Classify a commit by its Conventional Commit type
+///
+/// Breaking changes are always user-facing, so they land in `Changed` unless
+/// their type already maps to a category.
+pub fn classify(commit: &CommitInfo) -> Classification {
+    let message = format!("{}\n\n{}", commit.subject, commit.body);
+    let Ok(parsed) = parse(&message) else {
+        return Classification::Unknown;
+    };
+
+    let category = match parsed.commit_type.as_str() {
+        "feat" => Some(ChangeCategory::Added),
+        "fix" => Some(ChangeCategory::Fixed),
+        "perf" | "refactor" => Some(ChangeCategory::Changed),
+        // Undoing a change is not "Removed" in the Keep a Changelog sense
+        "revert" => return Classification::Revert,
+        "security" => Some(ChangeCategory::Security),
+        "deprecate" => Some(ChangeCategory::Deprecated),
+        "docs" | "style" | "test" | "chore" | "ci" | "build" => None,
+        _ => return Classification::Unknown,
+    };
+
+    match category {
+        Some(category) => Classification::Category(category),
+        None if parsed.breaking => Classification::Category(ChangeCategory::Changed),
+        None => Classification::Internal,
+    }
+}
+
+/// Heading of a release section, e.g. `## [1.2.0] - 2024-05-01`
+pub fn release_heading(version: &str, date: Option<&str>) -> String {
+    match date {
+        Some(date) if version != UNRELEASED => format!("## [{}] - {}", version, date),
+        _ => format!("## [{}]", version),
+    }
+}
+
+/// Insert or replace the section for `version` in a changelog
+///
+/// An existing section for the same version is replaced. New releases go
+/// below `[Unreleased]` and above older releases.
+pub fn update_changelog(existing: &str, version: &str, section: &str) -> String {
+    let section = format!("{}\n", section.trim_end());
+    if existing.trim().is_empty() {
+        return format!("{}\n{}", CHANGELOG_HEADER, section);
+    }
+
+    let lines: Vec<&str> = existing.lines().collect();
+    let is_release = |line: &str| line.starts_with("## [");
+    let is_version = |line: &str, version: &str| {
+        line.strip_prefix("## [")
+            .and_then(|rest| rest.split_once(']'))
+            .is_some_and(|(name, _)| name.eq_ignore_ascii_case(version))
+    };
+    let section_end = |start: usize| {
+        lines[start + 1..]
+            .iter()
+            .position(|line| is_release(line) || is_link_reference(line))
+            .map(|offset| start + 1 + offset)
+            .unwrap_or(lines.len())
+    };
+
+    let (start, end) = if let Some(start) = lines.iter().position(|l| is_version(l, version)) {
+        (start, section_end(start))
+    } else if let Some(unreleased) = lines.iter().position(|l| is_version(l, UNRELEASED)) {
+        let end = section_end(unreleased);
+        (end, end)
+    } else {
+        let first = lines
+            .iter()
+            .position(|line| is_release(line) || is_link_reference(line))
+            .unwrap_or(lines.len());
+        (first, first)
+    };
+
+    let mut result = String::new();
+    for line in &lines[..start] {
+        result.push_str(line);
+        result.push('\n');
+    }
+    if !result.is_empty() && !result.ends_with("\n\n") {
+        result.push('\n');
+    }
+    result.push_str(&section);
+    if end < lines.len() {
+        result.push('\n');
+        for line in &lines[end..] {
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// Link definitions such as `[1.0.0]: https://...` at the end of the file
+fn is_link_reference(line: &str) -> bool {
+    line.starts_with('[') && line.contains("]: ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(subject: &str, body: &str) -> CommitInfo {
+        CommitInfo {
+            hash: "abc123".to_string(),
+            date: "2024-05-01".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            classify(&commit("feat(auth): add login", "")),
+            Classification::Category(ChangeCategory::Added)
+        );
+        assert_eq!(
+            classify(&commit("chore: bump deps", "")),
+            Classification::Internal
+        );
+        assert_eq!(
+            classify(&commit("build!: drop Node 16", "")),
+            Classification::Category(ChangeCategory::Changed)
+        );
+        assert_eq!(
+            classify(&commit("revert: add login", "")),
+            Classification::Revert
+        );
+        assert_eq!(classify(&commit("fix stuff", "")), Classification::Unknown);
+    }
+
+    #[test]
+    fn test_update_changelog() {
+        let created = update_changelog(
+            "",
+            "1.0.0",
+            "## [1.0.0] - 2024-05-01\n\n### Added\n\n- Login",
+        );
+        assert!(created.starts_with(CHANGELOG_HEADER));
+        assert!(created.ends_with("\n## [1.0.0] - 2024-05-01\n\n### Added\n\n- Login\n"));
+
+        let existing = "# Changelog\n\n## [Unreleased]\n\n- WIP\n\n## [1.0.0] - 2024-05-01\n\n- Login\n\n[1.0.0]: https://example.com\n";
+        let updated = update_changelog(existing, "1.1.0", "## [1.1.0] - 2024-06-01\n\n- Logout");
+        assert_eq!(
+            updated,
+            "# Changelog\n\n## [Unreleased]\n\n- WIP\n\n## [1.1.0] - 2024-06-01\n\n- Logout\n\n## [1.0.0] - 2024-05-01\n\n- Login\n\n[1.0.0]: https://example.com\n"
+        );
+
+        let replaced = update_changelog(existing, "1.0.0", "## [1.0.0] - 2024-05-01\n\n- Sign in");
+        assert_eq!(
+            replaced,
+            "# Changelog\n\n## [Unreleased]\n\n- WIP\n\n## [1.0.0] - 2024-05-01\n\n- Sign in\n\n[1.0.0]: https://example.com\n"
+        );
+    }
+}
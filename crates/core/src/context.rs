@@ -0,0 +1,223 @@
+use crate::diff::{FileChange, FilePatch};
+
+/// Default size of the rendered context in characters, below the point where
+/// providers truncate the user message
+pub const DEFAULT_CONTEXT_BUDGET: usize = 100_000;
+
+/// How much code around the staged changes is sent to the model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextOptions {
+    /// Widen hunks to their enclosing function or class
+    pub function_context: bool,
+    /// Include whole files with at most this many lines; 0 disables it
+    pub full_file_lines: usize,
+    /// Maximum size of the rendered context in characters
+    pub budget: usize,
+}
+
+impl Default for ContextOptions {
+    fn default() -> Self {
+        Self {
+            function_context: false,
+            full_file_lines: 0,
+            budget: DEFAULT_CONTEXT_BUDGET,
+        }
+    }
+}
+
+impl ContextOptions {
+    /// Whether any context beyond the plain diff is requested
+    pub fn is_enabled(&self) -> bool {
+        self.function_context || self.full_file_lines > 0
+    }
+}
+
+/// The staged change of one file with optional extra context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileContext {
+    pub change: FileChange,
+    /// The same change with whole enclosing functions as context
+    pub function_patch: Option<FilePatch>,
+    /// Staged contents of a small file
+    pub contents: Option<String>,
+}
+
+/// Marks a diff that was cut to fit the budget
+const TRUNCATED: &str = "[... diff truncated to fit the context budget]\n";
+
+/// Render the diff, widening files to richer context while it fits the budget
+///
+/// Files with the smallest diffs are widened first, since three lines of
+/// context tell the least about them. Full file contents are added after all
+/// function contexts. A plain diff larger than the budget is cut at a line
+/// boundary so the whole text stays within it.
+pub fn render_context(files: &[FileContext], budget: usize) -> String {
+    let mut diffs: Vec<String> = files.iter().map(|f| f.change.render_for_prompt()).collect();
+    let mut size: usize = diffs.iter().map(|d| chars(d)).sum();
+    let mut order: Vec<usize> = (0..files.len()).collect();
+    order.sort_by_key(|&i| chars(&diffs[i]));
+
+    for &i in &order {
+        if let Some(patch) = &files[i].function_patch
+            && !files[i].change.binary
+        {
+            let wider = files[i].change.render_patch_for_prompt(patch);
+            let grown = size - chars(&diffs[i]) + chars(&wider);
+            if grown <= budget {
+                size = grown;
+                diffs[i] = wider;
+            }
+        }
+    }
+
+    let mut sections: Vec<(usize, String)> = Vec::new();
+    for &i in &order {
+        if let Some(contents) = &files[i].contents {
+            let section = format!(
+                "### {}\n\n```\n{}\n```\n\n",
+                files[i].change.new_path,
+                contents.trim_end()
+            );
+            if size + chars(&section) <= budget {
+                size += chars(&section);
+                sections.push((i, section));
+            }
+        }
+    }
+    sections.sort_by_key(|(i, _)| *i);
+
+    let mut text = diffs.concat();
+    if !sections.is_empty() {
+        text.push_str("\n## Full contents of small changed files\n\n");
+        for (_, section) in sections {
+            text.push_str(&section);
+        }
+    }
+    if chars(&text) > budget {
+        truncate(&mut text, budget.saturating_sub(chars(TRUNCATED)));
+        text.push_str(TRUNCATED);
+    }
+    text
+}
+
+/// Size of `text` as counted against the budget
+fn chars(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Cut `text` to at most `max` characters, after the last complete line
+fn truncate(text: &mut String, max: usize) {
+    let end = text.char_indices().nth(max).map_or(text.len(), |(i, _)| i);
+    let end = text[..end].rfind('\n').map_or(0, |i| i + 1);
+    text.truncate(end);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{ChangeKind, DiffStats, Hunk};
+
+    fn change(patch: FilePatch) -> FileChange {
+        FileChange {
+            kind: ChangeKind::Modified,
+            old_path: patch.path.clone(),
+            new_path: patch.path.clone(),
+            old_mode: None,
+            new_mode: None,
+            old_id: None,
+            new_id: None,
+            binary: false,
+            old_size: None,
+            new_size: None,
+            submodule_log: Vec::new(),
+            stats: DiffStats::default(),
+            patch,
+        }
+    }
+
+    fn hunk(count: usize) -> Hunk {
+        Hunk {
+            header: "@@ -1 +1 @@".to_string(),
+            lines: (0..count).map(|n| format!(" line {}", n)).collect(),
+        }
+    }
+
+    fn file(path: &str, lines: usize) -> FileContext {
+        FileContext {
+            change: change(FilePatch {
+                path: path.to_string(),
+                header: vec![format!("diff --git a/{} b/{}", path, path)],
+                hunks: vec![hunk(lines)],
+            }),
+            function_patch: Some(FilePatch {
+                path: path.to_string(),
+                header: vec![format!("diff --git a/{} b/{}", path, path)],
+                hunks: vec![hunk(lines + 10)],
+            }),
+            contents: Some("fn main() {}\n".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_render_context_within_budget() {
+        let files = vec![file("big.rs", 20), file("small.rs", 1)];
+        let plain: String = files.iter().map(|f| f.change.render_for_prompt()).collect();
+        assert_eq!(render_context(&files, plain.len()), plain);
+
+        // Room for one wider patch only goes to the smallest diff
+        let budget = plain.len() + 10 * " line 10\n".len();
+        let text = render_context(&files, budget);
+        assert!(text.contains("diff --git a/small.rs b/small.rs\n@@ -1 +1 @@\n line 0\n line 1\n"));
+        assert!(!text.contains(" line 29"));
+
+        let text = render_context(&files, usize::MAX);
+        assert!(text.contains(" line 29"));
+        assert!(text.ends_with(
+            "### big.rs\n\n```\nfn main() {}\n```\n\n### small.rs\n\n```\nfn main() {}\n```\n\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_context_keeps_summary() {
+        let mut renamed = file("new.rs", 1);
+        renamed.change.kind = ChangeKind::Renamed;
+        renamed.change.old_path = "old.rs".to_string();
+        renamed.contents = None;
+        let text = render_context(&[renamed], usize::MAX);
+        assert!(text.starts_with("renamed old.rs → new.rs\ndiff --git"));
+        assert!(text.contains(" line 10"));
+    }
+
+    #[test]
+    fn test_render_context_truncates_plain_diff() {
+        let mut large = file("large.rs", 100);
+        large.function_patch = None;
+        large.contents = None;
+        let budget = 200;
+        let text = render_context(&[large], budget);
+        assert!(text.len() <= budget);
+        assert!(text.starts_with("diff --git a/large.rs b/large.rs\n@@ -1 +1 @@\n line 0\n"));
+        assert!(text.ends_with(&format!("\n{}", TRUNCATED)));
+    }
+
+    #[test]
+    fn test_render_context_counts_characters() {
+        let mut large = file("large.rs", 0);
+        large.function_patch = None;
+        large.contents = None;
+        large.change.patch.hunks[0].lines = (0..50).map(|n| format!("+строка {}", n)).collect();
+        let plain = large.change.render_for_prompt();
+        assert!(plain.len() > chars(&plain));
+
+        // A budget counted in characters keeps the whole non-ASCII diff
+        assert_eq!(
+            render_context(std::slice::from_ref(&large), chars(&plain)),
+            plain
+        );
+
+        let text = render_context(&[large], 200);
+        assert!(chars(&text) <= 200);
+        assert!(text.len() > 200);
+        assert!(text.ends_with(&format!("\n{}", TRUNCATED)));
+    }
+}
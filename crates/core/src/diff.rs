@@ -0,0 +1,370 @@
+/// A single `@@` hunk of a file patch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The `@@ -a,b +c,d @@` line
+    pub header: String,
+    /// Context, added and removed lines including their prefix
+    pub lines: Vec<String>,
+}
+
+/// The patch of a single file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    /// Path of the file after the change
+    pub path: String,
+    /// Lines from `diff --git` up to the first hunk
+    pub header: Vec<String>,
+    /// Hunks of the patch; empty for binary files, pure renames and mode changes
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// Whether the patch must be applied as a whole rather than hunk by hunk
+    ///
+    /// Creations, deletions, renames, copies, mode changes and binary patches
+    /// only make sense together with all of their hunks.
+    pub fn is_atomic(&self) -> bool {
+        self.hunks.is_empty()
+            || self.header.iter().any(|line| {
+                [
+                    "new file mode",
+                    "deleted file mode",
+                    "rename from",
+                    "copy from",
+                    "old mode",
+                    "GIT binary patch",
+                    "Binary files",
+                ]
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+            })
+    }
+
+    /// Copy of this patch containing only the selected hunks
+    pub fn with_hunks(&self, indices: &[usize]) -> Self {
+        Self {
+            path: self.path.clone(),
+            header: self.header.clone(),
+            hunks: indices
+                .iter()
+                .filter_map(|&i| self.hunks.get(i).cloned())
+                .collect(),
+        }
+    }
+
+    /// Render the patch back to `git apply` compatible text
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        for line in &self.header {
+            text.push_str(line);
+            text.push('\n');
+        }
+        for hunk in &self.hunks {
+            text.push_str(&hunk.header);
+            text.push('\n');
+            for line in &hunk.lines {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+/// How a file was changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    /// File mode changed, possibly along with the content
+    ModeChanged,
+}
+
+impl ChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+            Self::Renamed => "renamed",
+            Self::Copied => "copied",
+            Self::ModeChanged => "mode changed",
+        }
+    }
+}
+
+/// Number of added and removed lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+impl std::ops::Add for DiffStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            additions: self.additions + other.additions,
+            deletions: self.deletions + other.deletions,
+        }
+    }
+}
+
+impl std::iter::Sum for DiffStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, stats| total + stats)
+    }
+}
+
+/// A change to a single file together with its patch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    /// Path before the change; differs from `new_path` for renames and copies
+    pub old_path: String,
+    /// Path after the change; the deleted path for deletions
+    pub new_path: String,
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    /// Object ids from the `index` line; `None` on the side where the file is missing
+    pub old_id: Option<String>,
+    pub new_id: Option<String>,
+    /// Binary files have no hunks and no line stats
+    pub binary: bool,
+    /// Blob sizes in bytes of binary files, when known
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    /// `git diff --submodule=log` lines of a submodule, e.g. `> Fix parser`
+    pub submodule_log: Vec<String>,
+    pub stats: DiffStats,
+    pub patch: FilePatch,
+}
+
+/// Mode git uses for submodule entries
+const SUBMODULE_MODE: &str = "160000";
+
+impl FileChange {
+    /// Whether the entry is a submodule pointer rather than a file
+    pub fn is_submodule(&self) -> bool {
+        self.new_mode.as_deref() == Some(SUBMODULE_MODE)
+            || self.old_mode.as_deref() == Some(SUBMODULE_MODE)
+    }
+
+    /// Compact description of changes whose patch tells the model little
+    ///
+    /// Covers renames, copies, binary files, submodule bumps and mode changes;
+    /// `None` for plain edits, additions and deletions.
+    pub fn summary(&self) -> Option<String> {
+        if self.is_submodule() {
+            return Some(self.submodule_summary());
+        }
+        if self.binary {
+            let kind = binary_kind(&self.new_path);
+            let sizes = match (self.old_size, self.new_size) {
+                (Some(old), Some(new)) => format!(" ({} → {})", format_size(old), format_size(new)),
+                (Some(size), None) | (None, Some(size)) => format!(" ({})", format_size(size)),
+                (None, None) => String::new(),
+            };
+            let action = match self.kind {
+                ChangeKind::Added => "added",
+                ChangeKind::Deleted => "deleted",
+                _ => "updated",
+            };
+            return Some(match self.kind {
+                ChangeKind::Renamed | ChangeKind::Copied => format!(
+                    "binary {} {} {} → {}{}",
+                    kind,
+                    self.kind.as_str(),
+                    self.old_path,
+                    self.new_path,
+                    sizes
+                ),
+                _ => format!("binary {} {} {}{}", kind, self.new_path, action, sizes),
+            });
+        }
+
+        let mut summary = match self.kind {
+            ChangeKind::Renamed | ChangeKind::Copied => Some(format!(
+                "{} {} → {}",
+                self.kind.as_str(),
+                self.old_path,
+                self.new_path
+            )),
+            _ => None,
+        };
+        if let (Some(old), Some(new)) = (&self.old_mode, &self.new_mode)
+            && old != new
+            && !matches!(self.kind, ChangeKind::Added | ChangeKind::Deleted)
+        {
+            let mode = match (old.as_str(), new.as_str()) {
+                ("100644", "100755") => format!("made {} executable", self.new_path),
+                ("100755", "100644") => format!("made {} non-executable", self.new_path),
+                _ => format!("changed mode of {} {} → {}", self.new_path, old, new),
+            };
+            summary = Some(match summary {
+                Some(rename) => format!("{}; {}", rename, mode),
+                None => mode,
+            });
+        }
+        summary
+    }
+
+    fn submodule_summary(&self) -> String {
+        let short = |id: &Option<String>| {
+            id.as_deref()
+                .map(|id| id.chars().take(7).collect::<String>())
+                .unwrap_or_default()
+        };
+        let path = &self.new_path;
+        let mut summary = match self.kind {
+            ChangeKind::Added => format!("submodule {} added at {}", path, short(&self.new_id)),
+            ChangeKind::Deleted => format!("submodule {} removed", path),
+            _ => {
+                let added = self
+                    .submodule_log
+                    .iter()
+                    .filter(|l| l.starts_with('>'))
+                    .count();
+                let removed = self
+                    .submodule_log
+                    .iter()
+                    .filter(|l| l.starts_with('<'))
+                    .count();
+                match (added, removed) {
+                    (0, 0) => format!(
+                        "submodule {} updated {}..{}",
+                        path,
+                        short(&self.old_id),
+                        short(&self.new_id)
+                    ),
+                    (added, 0) => {
+                        format!("submodule {} bumped {} {}:", path, added, commits(added))
+                    }
+                    (0, removed) => {
+                        format!(
+                            "submodule {} rewound {} {}:",
+                            path,
+                            removed,
+                            commits(removed)
+                        )
+                    }
+                    (added, removed) => format!(
+                        "submodule {} moved to a different history (+{}/-{} commits):",
+                        path, added, removed
+                    ),
+                }
+            }
+        };
+        if !matches!(self.kind, ChangeKind::Added | ChangeKind::Deleted) {
+            for line in &self.submodule_log {
+                summary.push_str("\n  ");
+                summary.push_str(line);
+            }
+        }
+        summary
+    }
+
+    /// Render the change for the model, replacing noise with its summary
+    ///
+    /// Binary data, submodule pointers and header-only patches are replaced
+    /// entirely; renames and mode changes with edits keep their hunks.
+    pub fn render_for_prompt(&self) -> String {
+        self.render_patch_for_prompt(&self.patch)
+    }
+
+    /// Render `patch`, e.g. the change with wider context, in place of this
+    /// change's own patch, keeping the summary line
+    pub fn render_patch_for_prompt(&self, patch: &FilePatch) -> String {
+        let Some(summary) = self.summary() else {
+            return patch.render();
+        };
+        if self.binary || self.is_submodule() || patch.hunks.is_empty() {
+            return format!("{}\n", summary);
+        }
+        format!("{}\n{}", summary, patch.render())
+    }
+}
+
+fn commits(count: usize) -> &'static str {
+    if count == 1 { "commit" } else { "commits" }
+}
+
+/// Rough category of a binary file for its summary
+fn binary_kind(path: &str) -> &'static str {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "ico" | "bmp" | "tiff" | "avif" => "image",
+        "ttf" | "otf" | "woff" | "woff2" => "font",
+        "zip" | "gz" | "tgz" | "xz" | "bz2" | "7z" | "jar" | "tar" => "archive",
+        "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" => "document",
+        "mp3" | "wav" | "ogg" | "flac" | "mp4" | "mov" | "webm" => "media file",
+        _ => "file",
+    }
+}
+
+/// Human readable size such as `12KB`
+fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    if bytes < KB {
+        format!("{}B", bytes)
+    } else if bytes < MB {
+        format!("{}KB", (bytes + KB / 2) / KB)
+    } else {
+        format!("{:.1}MB", bytes as f64 / MB as f64)
+    }
+}
+
+/// A set of file changes, such as everything that is staged
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSet {
+    pub files: Vec<FileChange>,
+}
+
+impl ChangeSet {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Paths after the change
+    pub fn paths(&self) -> Vec<String> {
+        self.files.iter().map(|f| f.new_path.clone()).collect()
+    }
+
+    /// Line stats summed over all files
+    pub fn stats(&self) -> DiffStats {
+        self.files.iter().map(|f| f.stats).sum()
+    }
+
+    /// Only the files matching `keep`
+    pub fn filter(&self, keep: impl Fn(&FileChange) -> bool) -> Self {
+        Self {
+            files: self.files.iter().filter(|f| keep(f)).cloned().collect(),
+        }
+    }
+
+    /// Render the changes back to patch text
+    pub fn render(&self) -> String {
+        self.files.iter().map(|f| f.patch.render()).collect()
+    }
+
+    /// Render the changes for the model with compact summaries
+    pub fn render_for_prompt(&self) -> String {
+        self.files
+            .iter()
+            .map(FileChange::render_for_prompt)
+            .collect()
+    }
+}
+
+/// Render several file patches into one patch
+pub fn render_patches(patches: &[FilePatch]) -> String {
+    patches.iter().map(FilePatch::render).collect()
+}
@@ -1,4 +1,60 @@
 use async_trait::async_trait;
+use std::path::PathBuf;
+
+use crate::diff::{ChangeSet, FilePatch};
+use crate::message::parser::Footer;
+
+/// Options passed through to `git commit`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitOptions {
+    /// `Some(true)` signs (`-S`), `Some(false)` disables signing, `None` follows `commit.gpgsign`
+    pub sign: Option<bool>,
+    /// Key to sign with (`-S<keyid>`)
+    pub signing_key: Option<String>,
+    /// Bypass pre-commit and commit-msg hooks (`--no-verify`)
+    pub no_verify: bool,
+    /// Override the commit author (`--author`)
+    pub author: Option<String>,
+    /// Override the author date (`--date`)
+    pub date: Option<String>,
+    /// Allow a commit without changes (`--allow-empty`)
+    pub allow_empty: bool,
+    /// Message cleanup mode (`--cleanup`)
+    pub cleanup: Option<String>,
+    /// Raw arguments appended to `git commit`
+    pub extra_args: Vec<String>,
+}
+
+/// A commit as listed by `git log`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    pub hash: String,
+    /// Author date as `YYYY-MM-DD`
+    pub date: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Kind of merge waiting to be committed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeKind {
+    /// `git merge` stopped before committing (`MERGE_HEAD` exists)
+    Merge,
+    /// `git merge --squash` staged the changes (`SQUASH_MSG` exists)
+    Squash,
+}
+
+/// An in-progress merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeState {
+    pub kind: MergeKind,
+    /// Name of the merged branch, tag or commit, if git recorded it
+    pub incoming: Option<String>,
+    /// Commits brought in by the merge, oldest first
+    pub commits: Vec<CommitInfo>,
+    /// Files that had conflicts
+    pub conflicts: Vec<String>,
+}
 
 /// Interface for git repository operations
 #[async_trait]
@@ -6,6 +62,79 @@ pub trait GitRepository: Send + Sync {
     /// Get the diff of staged changes
     async fn get_staged_diff(&self) -> anyhow::Result<String>;
 
-    /// Execute git commit with the given message
-    async fn commit(&self, message: &str) -> anyhow::Result<()>;
+    /// Get the paths of staged files relative to the repository root
+    async fn get_staged_files(&self) -> anyhow::Result<Vec<String>>;
+
+    /// Get the staged changes as per-file patches that can be staged again
+    async fn get_staged_patches(&self) -> anyhow::Result<Vec<FilePatch>>;
+
+    /// Get the staged changes with their kind, paths, modes and line stats
+    async fn get_staged_changes(&self) -> anyhow::Result<ChangeSet>;
+
+    /// Like [`Self::get_staged_patches`], with each hunk widened to its enclosing
+    /// function or class (`git diff --function-context`)
+    async fn get_staged_function_patches(&self) -> anyhow::Result<Vec<FilePatch>>;
+
+    /// Get the staged contents of a file, or `None` if it is deleted or binary
+    async fn get_staged_file(&self, path: &str) -> anyhow::Result<Option<String>>;
+
+    /// Reset the index to HEAD, leaving the working tree untouched
+    async fn reset_index(&self) -> anyhow::Result<()>;
+
+    /// Apply patches to the index only, like `git apply --cached`
+    async fn stage_patches(&self, patches: &[FilePatch]) -> anyhow::Result<()>;
+
+    /// Get the top-level directory of the working tree
+    async fn root_dir(&self) -> anyhow::Result<PathBuf>;
+
+    /// Get the in-progress merge or squash merge, if any
+    async fn merge_state(&self) -> anyhow::Result<Option<MergeState>>;
+
+    /// List the commits in a revision range, oldest first
+    async fn log(&self, range: &str) -> anyhow::Result<Vec<CommitInfo>>;
+
+    /// Get the cumulative diff of a revision range
+    async fn diff_range(&self, range: &str) -> anyhow::Result<String>;
+
+    /// Get each commit of a revision range with its patch, oldest first
+    ///
+    /// Hunks include the whole enclosing function (`--function-context`) so the
+    /// change can be understood without checking out the commit.
+    async fn show_range(&self, range: &str) -> anyhow::Result<String>;
+
+    /// Move HEAD to `rev`, keeping the index and working tree (`git reset --soft`)
+    async fn soft_reset(&self, rev: &str) -> anyhow::Result<()>;
+
+    /// Get the highest semantic version tag reachable from HEAD, if any
+    async fn latest_tag(&self) -> anyhow::Result<Option<String>>;
+
+    /// Create an annotated tag on HEAD
+    async fn create_tag(&self, name: &str, message: &str) -> anyhow::Result<()>;
+
+    /// Create a branch at HEAD and switch to it (`git switch -c`)
+    async fn create_branch(&self, name: &str) -> anyhow::Result<()>;
+
+    /// Get the name of the checked out branch, or `None` on a detached HEAD
+    async fn current_branch(&self) -> anyhow::Result<Option<String>>;
+
+    /// Get the committer identity as `Name <email>`
+    async fn committer_identity(&self) -> anyhow::Result<String>;
+
+    /// Get distinct recent commit authors as `Name <email>`, honoring `.mailmap`
+    async fn recent_authors(&self, limit: usize) -> anyhow::Result<Vec<String>>;
+
+    /// Add trailers to a message using `git interpret-trailers`
+    async fn add_trailers(&self, message: &str, trailers: &[Footer]) -> anyhow::Result<String>;
+
+    /// Execute git commit with the given message and options
+    async fn commit(&self, message: &str, options: &CommitOptions) -> anyhow::Result<()>;
+
+    /// Keep a message in `.git/GIT_GEN_MSG` so a failed commit can be retried
+    async fn save_message(&self, message: &str) -> anyhow::Result<()>;
+
+    /// Get the message kept by `save_message`, if any
+    async fn saved_message(&self) -> anyhow::Result<Option<String>>;
+
+    /// Remove the message kept by `save_message`
+    async fn clear_saved_message(&self) -> anyhow::Result<()>;
 }
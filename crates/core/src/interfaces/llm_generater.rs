@@ -1,6 +1,110 @@
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Input for a single commit message generation
+#[derive(Debug, Clone, Default)]
+pub struct GenerationRequest {
+    /// Staged diff to describe
+    pub diff: String,
+    /// Extra instructions appended to the system prompt
+    pub instructions: Vec<String>,
+}
+
+impl GenerationRequest {
+    /// Create a request for the given diff without extra instructions
+    pub fn new(diff: String) -> Self {
+        Self {
+            diff,
+            instructions: Vec::new(),
+        }
+    }
+}
+
+/// A provider request as it would be sent, for auditing without network access
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RequestPreview {
+    pub provider: String,
+    pub model: String,
+    /// Endpoint with credentials redacted
+    pub url: String,
+    /// Request headers with credentials redacted
+    pub headers: BTreeMap<String, String>,
+    /// JSON body exactly as it would be sent
+    pub body: serde_json::Value,
+    /// Characters of the user message before truncation
+    pub original_length: usize,
+    /// Characters of the user message as sent
+    pub sent_length: usize,
+    pub truncated: bool,
+    /// Credentials left out of the printed request
+    pub redacted: Vec<String>,
+    /// Rough token count of the system prompt and user message
+    pub estimated_tokens: usize,
+}
+
+/// Tokens billed for one or more requests, as reported by the provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+}
 
 #[async_trait]
 pub trait LlmGenerater: Send + Sync {
-    async fn generate_commit_message(&self, diff: String) -> anyhow::Result<String>;
+    async fn generate_commit_message(&self, request: GenerationRequest) -> anyhow::Result<String>;
+
+    /// Run a free-form completion with the given system prompt and user message
+    async fn complete(&self, system_prompt: &str, user_message: String) -> anyhow::Result<String>;
+
+    /// Describe the request `generate_commit_message` would send, without sending it
+    fn preview_commit_message(&self, request: GenerationRequest) -> RequestPreview;
+
+    /// Describe the request `complete` would send, without sending it
+    fn preview(&self, system_prompt: &str, user_message: String) -> RequestPreview;
+
+    /// Tokens used by all requests sent so far
+    fn usage(&self) -> TokenUsage;
+}
+
+/// Share a generater, e.g. to read its usage after handing it to a service
+#[async_trait]
+impl<T: LlmGenerater + ?Sized> LlmGenerater for Arc<T> {
+    async fn generate_commit_message(&self, request: GenerationRequest) -> anyhow::Result<String> {
+        (**self).generate_commit_message(request).await
+    }
+
+    async fn complete(&self, system_prompt: &str, user_message: String) -> anyhow::Result<String> {
+        (**self).complete(system_prompt, user_message).await
+    }
+
+    fn preview_commit_message(&self, request: GenerationRequest) -> RequestPreview {
+        (**self).preview_commit_message(request)
+    }
+
+    fn preview(&self, system_prompt: &str, user_message: String) -> RequestPreview {
+        (**self).preview(system_prompt, user_message)
+    }
+
+    fn usage(&self) -> TokenUsage {
+        (**self).usage()
+    }
 }
@@ -1,7 +1,33 @@
+pub mod branch;
+pub mod changelog;
+pub mod context;
+pub mod diff;
 pub mod interfaces;
+pub mod message;
+pub mod prompts;
 pub mod services;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod version;
 
 // Re-export commonly used types
-pub use interfaces::git_repository::GitRepository;
-pub use interfaces::llm_generater::LlmGenerater;
+pub use branch::BranchParts;
+pub use context::ContextOptions;
+pub use diff::{ChangeKind, ChangeSet, DiffStats, FileChange, FilePatch, Hunk};
+pub use interfaces::git_repository::{
+    CommitInfo, CommitOptions, GitRepository, MergeKind, MergeState,
+};
+pub use interfaces::llm_generater::{GenerationRequest, LlmGenerater, RequestPreview, TokenUsage};
+pub use message::parser::{ConventionalCommit, Footer};
+pub use message::scope::ScopeMapping;
+pub use message::style::{MessageStyle, SubjectCase};
+pub use message::ticket::{TicketPlacement, TicketReference};
+pub use message::validator::{CommitRules, HeaderFormat, Violation};
+pub use services::branch_service::{BranchService, BranchServiceImpl, BranchSuggestion};
+pub use services::bump_service::{BumpRecommendation, BumpService, BumpServiceImpl};
+pub use services::changelog_service::{ChangelogService, ChangelogServiceImpl};
 pub use services::commit_service::{CommitService, CommitServiceImpl};
+pub use services::explain_service::{ExplainService, ExplainServiceImpl};
+pub use services::pr_service::{PrService, PrServiceImpl, PullRequest};
+pub use services::review_service::{Finding, Review, ReviewService, ReviewServiceImpl, Severity};
+pub use services::split_service::{SplitPlan, SplitService, SplitServiceImpl};
@@ -0,0 +1,172 @@
+use super::parser::parse;
+
+/// Common characters that differ between Simplified and Traditional Chinese,
+/// as `(simplified, traditional)` pairs
+const SIMPLIFIED_TRADITIONAL: &[(char, char)] = &[
+    ('这', '這'),
+    ('个', '個'),
+    ('们', '們'),
+    ('为', '為'),
+    ('说', '說'),
+    ('时', '時'),
+    ('实', '實'),
+    ('现', '現'),
+    ('复', '復'),
+    ('发', '發'),
+    ('开', '開'),
+    ('关', '關'),
+    ('务', '務'),
+    ('动', '動'),
+    ('数', '數'),
+    ('据', '據'),
+    ('处', '處'),
+    ('应', '應'),
+    ('该', '該'),
+    ('进', '進'),
+    ('还', '還'),
+    ('对', '對'),
+    ('从', '從'),
+    ('问', '問'),
+    ('题', '題'),
+    ('无', '無'),
+    ('产', '產'),
+    ('类', '類'),
+    ('档', '檔'),
+    ('转', '轉'),
+    ('错', '錯'),
+    ('误', '誤'),
+    ('码', '碼'),
+    ('单', '單'),
+    ('页', '頁'),
+    ('显', '顯'),
+    ('设', '設'),
+    ('户', '戶'),
+    ('态', '態'),
+    ('级', '級'),
+    ('线', '線'),
+    ('网', '網'),
+    ('络', '絡'),
+    ('项', '項'),
+    ('库', '庫'),
+    ('测', '測'),
+    ('试', '試'),
+    ('环', '環'),
+    ('变', '變'),
+    ('删', '刪'),
+    ('录', '錄'),
+    ('优', '優'),
+    ('构', '構'),
+    ('调', '調'),
+    ('败', '敗'),
+    ('认', '認'),
+    ('证', '證'),
+];
+
+/// Human-readable name of a BCP 47 language tag such as `zh-TW`
+pub fn language_name(tag: &str) -> String {
+    let name = match tag.to_ascii_lowercase().as_str() {
+        "en" | "en-us" | "en-gb" => "English",
+        "zh-tw" | "zh-hk" | "zh-hant" => "Traditional Chinese",
+        "zh" | "zh-cn" | "zh-sg" | "zh-hans" => "Simplified Chinese",
+        "ja" | "ja-jp" => "Japanese",
+        "ko" | "ko-kr" => "Korean",
+        "de" => "German",
+        "fr" => "French",
+        "es" => "Spanish",
+        "pt" | "pt-br" => "Portuguese",
+        "it" => "Italian",
+        "ru" => "Russian",
+        "uk" => "Ukrainian",
+        "vi" => "Vietnamese",
+        _ => return tag.to_string(),
+    };
+    format!("{} ({})", name, tag)
+}
+
+/// Describe the language as an instruction for the LLM prompt
+pub fn language_instruction(tag: &str) -> String {
+    format!(
+        "Write the subject and body in {}. Keep the Conventional Commits type and scope, trailer tokens such as `BREAKING CHANGE` and `Refs`, and code identifiers in English",
+        language_name(tag)
+    )
+}
+
+/// Check whether the prose of a message is written in the language
+///
+/// Only the script is checked, so languages written in the Latin alphabet
+/// other than English always pass. Chinese variants are told apart by
+/// characters that exist in only one of them.
+pub fn is_in_language(message: &str, tag: &str) -> bool {
+    let prose = match parse(message) {
+        Ok(commit) => format!("{}\n{}", commit.subject, commit.body.unwrap_or_default()),
+        Err(_) => message.to_string(),
+    };
+    let has = |f: fn(char) -> bool| prose.chars().any(f);
+
+    let tag = tag.to_ascii_lowercase();
+    let (language, region) = tag.split_once(['-', '_']).unwrap_or((&tag, ""));
+    match language {
+        "zh" => {
+            if !has(is_han) || has(is_kana) {
+                return false;
+            }
+            match region {
+                "tw" | "hk" | "mo" | "hant" => !prose
+                    .chars()
+                    .any(|c| SIMPLIFIED_TRADITIONAL.iter().any(|(s, _)| *s == c)),
+                "" | "cn" | "sg" | "hans" => !prose
+                    .chars()
+                    .any(|c| SIMPLIFIED_TRADITIONAL.iter().any(|(_, t)| *t == c)),
+                _ => true,
+            }
+        }
+        "ja" => has(is_kana),
+        "ko" => has(is_hangul),
+        "ru" | "uk" | "bg" | "sr" | "be" => has(is_cyrillic),
+        "en" => !has(|c| is_han(c) || is_kana(c) || is_hangul(c) || is_cyrillic(c)),
+        _ => true,
+    }
+}
+
+fn is_han(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}')
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}')
+}
+
+fn is_cyrillic(c: char) -> bool {
+    matches!(c, '\u{0400}'..='\u{04FF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_in_language() {
+        assert!(is_in_language(
+            "feat(auth): 新增登入功能\n\n支援 OAuth 登入",
+            "zh-TW"
+        ));
+        assert!(!is_in_language("feat(auth): 新增登录功能", "zh-TW"));
+        assert!(is_in_language("feat(auth): 新增登录功能", "zh-CN"));
+        assert!(!is_in_language("feat(auth): add login", "zh-TW"));
+        assert!(is_in_language("fix: ログインのバグを修正", "ja"));
+        assert!(!is_in_language("fix: 修正登入錯誤", "ja"));
+        assert!(is_in_language("fix: add login\n\nRefs: 42", "en"));
+        assert!(!is_in_language("fix: 修正登入錯誤", "en"));
+        assert!(is_in_language("fix: Anmeldung korrigieren", "de"));
+    }
+
+    #[test]
+    fn test_language_name() {
+        assert_eq!(language_name("zh-TW"), "Traditional Chinese (zh-TW)");
+        assert_eq!(language_name("tlh"), "tlh");
+    }
+}
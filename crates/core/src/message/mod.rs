@@ -0,0 +1,7 @@
+pub mod language;
+pub mod parser;
+pub mod sanitizer;
+pub mod scope;
+pub mod style;
+pub mod ticket;
+pub mod validator;
@@ -0,0 +1,247 @@
+use std::fmt;
+
+/// A single trailer-style footer, e.g. `Refs: #123` or `BREAKING CHANGE: ...`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footer {
+    pub token: String,
+    pub value: String,
+}
+
+/// Parsed representation of a Conventional Commits message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    /// Commit type, e.g. `feat` or `fix`
+    pub commit_type: String,
+    /// Optional scope inside parentheses
+    pub scope: Option<String>,
+    /// Whether the header carries the `!` breaking change marker
+    pub breaking: bool,
+    /// Subject line after `type(scope): `
+    pub subject: String,
+    /// Free-form body between the header and the footers
+    pub body: Option<String>,
+    /// Footers in the last paragraph of the message
+    pub footers: Vec<Footer>,
+}
+
+/// Error returned when a header does not follow `type(scope)!: subject`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError(pub String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Components of a `type(scope)!: subject` header line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub subject: String,
+}
+
+/// Parse a header line of the form `type(scope)!: subject`
+pub fn parse_header(line: &str) -> Result<Header, ParseError> {
+    let colon = line
+        .find(':')
+        .ok_or_else(|| ParseError("header is missing the `type: subject` separator".into()))?;
+    let (prefix, rest) = line.split_at(colon);
+
+    let subject = rest[1..]
+        .strip_prefix(' ')
+        .ok_or_else(|| ParseError("expected a single space after `:` in the header".into()))?
+        .to_string();
+
+    let (prefix, breaking) = match prefix.strip_suffix('!') {
+        Some(p) => (p, true),
+        None => (prefix, false),
+    };
+
+    let (commit_type, scope) = match prefix.find('(') {
+        Some(open) => {
+            let scope = prefix[open + 1..]
+                .strip_suffix(')')
+                .ok_or_else(|| ParseError("scope is missing a closing `)`".into()))?;
+            if scope.is_empty() {
+                return Err(ParseError("scope must not be empty".into()));
+            }
+            (&prefix[..open], Some(scope.to_string()))
+        }
+        None => (prefix, None),
+    };
+
+    if commit_type.is_empty()
+        || !commit_type
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(ParseError(format!(
+            "`{}` is not a valid commit type",
+            commit_type
+        )));
+    }
+
+    Ok(Header {
+        commit_type: commit_type.to_string(),
+        scope,
+        breaking,
+        subject,
+    })
+}
+
+/// Parse a footer line such as `Refs: #12`, `Closes #34` or `BREAKING CHANGE: ...`
+pub fn parse_footer(line: &str) -> Option<Footer> {
+    for token in ["BREAKING CHANGE", "BREAKING-CHANGE"] {
+        if let Some(value) = line.strip_prefix(token).and_then(|r| r.strip_prefix(": ")) {
+            return Some(Footer {
+                token: token.to_string(),
+                value: value.to_string(),
+            });
+        }
+    }
+
+    let end = line.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))?;
+    if end == 0 {
+        return None;
+    }
+    let (token, rest) = line.split_at(end);
+    let value = rest.strip_prefix(": ").or_else(|| {
+        rest.strip_prefix(" #")
+            .map(|_| &rest[1..])
+            .filter(|v| v.len() > 1)
+    })?;
+
+    Some(Footer {
+        token: token.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Split a message into paragraphs separated by blank lines
+pub(crate) fn paragraphs(text: &str) -> Vec<Vec<&str>> {
+    let mut paragraphs = Vec::new();
+    let mut current = Vec::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+/// Parse footers from a paragraph, returning `None` if any line is not a footer
+pub(crate) fn parse_footer_paragraph(lines: &[&str]) -> Option<Vec<Footer>> {
+    let mut footers: Vec<Footer> = Vec::new();
+    for line in lines {
+        match parse_footer(line) {
+            Some(footer) => footers.push(footer),
+            // Indented lines continue the previous footer value
+            None if line.starts_with(char::is_whitespace) && !footers.is_empty() => {
+                let last = footers.last_mut().expect("checked non-empty");
+                last.value.push('\n');
+                last.value.push_str(line.trim());
+            }
+            None => return None,
+        }
+    }
+    Some(footers)
+}
+
+/// Parse a full commit message into its Conventional Commits parts
+pub fn parse(message: &str) -> Result<ConventionalCommit, ParseError> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(ParseError("commit message is empty".into()));
+    }
+    let (header_line, rest) = message.split_once('\n').unwrap_or((message, ""));
+    let header = parse_header(header_line.trim_end())?;
+
+    let mut paragraphs = paragraphs(rest);
+
+    let footers = match paragraphs.last().and_then(|p| parse_footer_paragraph(p)) {
+        Some(footers) => {
+            paragraphs.pop();
+            footers
+        }
+        None => Vec::new(),
+    };
+
+    let body = if paragraphs.is_empty() {
+        None
+    } else {
+        Some(
+            paragraphs
+                .iter()
+                .map(|p| p.join("\n"))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        )
+    };
+
+    let breaking = header.breaking
+        || footers
+            .iter()
+            .any(|f| f.token == "BREAKING CHANGE" || f.token == "BREAKING-CHANGE");
+
+    Ok(ConventionalCommit {
+        commit_type: header.commit_type,
+        scope: header.scope,
+        breaking,
+        subject: header.subject,
+        body,
+        footers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_message() {
+        let commit = parse(
+            "feat(llm)!: add streaming support\n\nStream tokens as they arrive.\n\nRefs: PROJ-12\nBREAKING CHANGE: generate now returns a stream",
+        )
+        .unwrap();
+
+        assert_eq!(commit.commit_type, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("llm"));
+        assert!(commit.breaking);
+        assert_eq!(commit.subject, "add streaming support");
+        assert_eq!(
+            commit.body.as_deref(),
+            Some("Stream tokens as they arrive.")
+        );
+        assert_eq!(commit.footers.len(), 2);
+        assert_eq!(commit.footers[0].token, "Refs");
+        assert_eq!(commit.footers[0].value, "PROJ-12");
+    }
+
+    #[test]
+    fn test_parse_header_errors() {
+        assert!(parse_header("add a feature").is_err());
+        assert!(parse_header("feat:no space").is_err());
+        assert!(parse_header("feat(: subject").is_err());
+        assert!(parse_header("feat(): subject").is_err());
+    }
+
+    #[test]
+    fn test_parse_footer_hash_separator() {
+        let footer = parse_footer("Closes #42").unwrap();
+        assert_eq!(footer.token, "Closes");
+        assert_eq!(footer.value, "#42");
+        assert!(parse_footer("Just a sentence: with colon").is_none());
+    }
+}
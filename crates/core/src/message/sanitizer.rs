@@ -0,0 +1,159 @@
+/// Preambles models like to put in front of the actual message
+const PREAMBLES: &[&str] = &[
+    "commit message:",
+    "suggested commit message:",
+    "here is the commit message:",
+    "here's the commit message:",
+    "here is a commit message:",
+    "here's a commit message:",
+    "here is your commit message:",
+    "here's your commit message:",
+];
+
+/// Repair common formatting mistakes in LLM output
+///
+/// Strips code fences, surrounding quotes and "Commit message:" preambles,
+/// removes trailing whitespace and ensures the header is followed by a blank line.
+pub fn sanitize(message: &str) -> String {
+    let mut message = message.trim().to_string();
+
+    // Repeat until stable since models sometimes combine several of these
+    loop {
+        let repaired = strip_quotes(&strip_code_fence(&strip_preamble(&message)));
+        if repaired == message {
+            break;
+        }
+        message = repaired;
+    }
+
+    let mut lines: Vec<String> = message.lines().map(|l| l.trim_end().to_string()).collect();
+    if lines.len() > 1 && !lines[1].is_empty() {
+        lines.insert(1, String::new());
+    }
+
+    // Collapse runs of blank lines
+    let mut result: Vec<String> = Vec::with_capacity(lines.len());
+    for line in lines {
+        if line.is_empty() && result.last().is_some_and(|l| l.is_empty()) {
+            continue;
+        }
+        result.push(line);
+    }
+
+    result.join("\n").trim().to_string()
+}
+
+/// Remove a leading "Commit message:" style preamble
+fn strip_preamble(message: &str) -> String {
+    let first_line = message.lines().next().unwrap_or_default();
+    let normalized = first_line.trim().trim_matches('*').trim().to_lowercase();
+
+    for preamble in PREAMBLES {
+        if normalized == *preamble {
+            return message[first_line.len()..].trim().to_string();
+        }
+        if normalized.starts_with(preamble) {
+            // Preamble and message share the first line
+            if let Some(pos) = message.find(':') {
+                return message[pos + 1..]
+                    .trim_start_matches('*')
+                    .trim()
+                    .to_string();
+            }
+        }
+    }
+
+    message.to_string()
+}
+
+/// Extract the content of a fenced code block if the message contains one
+pub(crate) fn strip_code_fence(message: &str) -> String {
+    let lines: Vec<&str> = message.lines().collect();
+    let Some(start) = lines.iter().position(|l| l.trim_start().starts_with("```")) else {
+        return message.to_string();
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.trim_start().starts_with("```"))
+        .map(|p| p + start + 1)
+        .unwrap_or(lines.len());
+
+    // Only unwrap when the fence holds the whole message or follows a preamble
+    let before_is_preamble = start == 0
+        || lines[..start].iter().all(|l| {
+            let l = l.trim();
+            l.is_empty() || l.ends_with(':')
+        });
+    if !before_is_preamble {
+        return message.to_string();
+    }
+
+    lines[start + 1..end].join("\n").trim().to_string()
+}
+
+/// Remove quotes or backticks wrapping the whole message
+///
+/// A message such as `"foo" now escapes "bar"` starts and ends with a quote
+/// without being wrapped in one, so an unescaped quote inside keeps it as is.
+fn strip_quotes(message: &str) -> String {
+    for quote in ['"', '\'', '`'] {
+        if message.len() >= 2 && message.starts_with(quote) && message.ends_with(quote) {
+            let inner = &message[1..message.len() - 1];
+            let unescaped = inner
+                .match_indices(quote)
+                .any(|(i, _)| !inner[..i].ends_with('\\'));
+            if !unescaped {
+                return inner.trim().to_string();
+            }
+        }
+    }
+    message.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_code_fence() {
+        assert_eq!(
+            sanitize("```\nfeat: add parser\n\nDetails here.\n```"),
+            "feat: add parser\n\nDetails here."
+        );
+        assert_eq!(
+            sanitize("Here is the commit message:\n\n```text\nfix: handle errors\n```"),
+            "fix: handle errors"
+        );
+    }
+
+    #[test]
+    fn test_strip_preamble_and_quotes() {
+        assert_eq!(sanitize("Commit message: \"fix: typo\""), "fix: typo");
+        assert_eq!(sanitize("**Commit message:**\nfix: typo"), "fix: typo");
+        assert_eq!(sanitize("`docs: update readme`"), "docs: update readme");
+        assert_eq!(
+            sanitize(r#""fix: escape \"raw\" strings""#),
+            r#"fix: escape \"raw\" strings"#
+        );
+    }
+
+    #[test]
+    fn test_keeps_quoted_identifiers() {
+        assert_eq!(
+            sanitize("`Config` now validates `Path`"),
+            "`Config` now validates `Path`"
+        );
+        assert_eq!(
+            sanitize(r#""foo" is now escaped like "bar""#),
+            r#""foo" is now escaped like "bar""#
+        );
+    }
+
+    #[test]
+    fn test_insert_blank_line_after_header() {
+        assert_eq!(
+            sanitize("feat: add parser  \nParse headers.\n\n\n\nRefs: #1"),
+            "feat: add parser\n\nParse headers.\n\nRefs: #1"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+/// Maps files matching a path glob to a commit scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeMapping {
+    /// Path glob relative to the repository root, e.g. `crates/llm/**`
+    pub pattern: String,
+    /// Scope used for matching files, e.g. `llm`
+    pub scope: String,
+}
+
+impl ScopeMapping {
+    pub fn new(pattern: impl Into<String>, scope: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            scope: scope.into(),
+        }
+    }
+}
+
+/// Infer scopes for the given files, most frequently touched first
+///
+/// When several patterns match a file the longest (most specific) one wins.
+pub fn detect_scopes(mappings: &[ScopeMapping], files: &[String]) -> Vec<String> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+
+    for file in files {
+        let best = mappings
+            .iter()
+            .filter(|m| glob_match(&m.pattern, file))
+            .max_by_key(|m| m.pattern.len());
+
+        if let Some(mapping) = best {
+            match counts.iter_mut().find(|(scope, _)| *scope == mapping.scope) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((mapping.scope.clone(), 1)),
+            }
+        }
+    }
+
+    // Stable sort keeps first-seen order for ties
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts.into_iter().map(|(scope, _)| scope).collect()
+}
+
+/// Describe detected scopes as an instruction for the LLM prompt
+pub fn scope_instruction(scopes: &[String]) -> Option<String> {
+    match scopes {
+        [] => None,
+        [scope] => Some(format!(
+            "All staged changes belong to the `{}` package; use `{}` as the scope",
+            scope, scope
+        )),
+        _ => Some(format!(
+            "The staged changes touch these packages: {}. Use the most relevant one as the scope, or omit the scope if the change spans all of them. Do not invent other scopes",
+            scopes.join(", ")
+        )),
+    }
+}
+
+/// Match a `/`-separated path against a glob supporting `*`, `?` and `**`
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                match_segment(segment.as_bytes(), name.as_bytes())
+                    && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| match_segment(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && match_segment(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && match_segment(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("crates/llm/**", "crates/llm/src/lib.rs"));
+        assert!(glob_match("crates/*/Cargo.toml", "crates/cli/Cargo.toml"));
+        assert!(glob_match("**/*.md", "README.md"));
+        assert!(glob_match("docs/?.md", "docs/a.md"));
+        assert!(!glob_match("crates/llm/**", "crates/llm-extra/src/lib.rs"));
+        assert!(!glob_match("crates/*", "crates/cli/src/main.rs"));
+    }
+
+    #[test]
+    fn test_detect_scopes() {
+        let mappings = vec![
+            ScopeMapping::new("crates/**", "crates"),
+            ScopeMapping::new("crates/llm/**", "llm"),
+            ScopeMapping::new("crates/cli/**", "cli"),
+        ];
+        let files = vec![
+            "crates/cli/src/main.rs".to_string(),
+            "crates/llm/src/lib.rs".to_string(),
+            "crates/llm/src/common.rs".to_string(),
+            "README.md".to_string(),
+        ];
+        assert_eq!(detect_scopes(&mappings, &files), vec!["llm", "cli"]);
+    }
+}
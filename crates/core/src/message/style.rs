@@ -0,0 +1,243 @@
+use super::validator::{CommitRules, HeaderFormat, parse_styled_header};
+
+/// Names of the built-in presets
+pub const PRESETS: &[&str] = &[
+    "conventional",
+    "gitmoji",
+    "angular",
+    "kernel",
+    "plain-sentence",
+    "one-line",
+];
+
+/// Types allowed by the Angular commit message guidelines
+const ANGULAR_TYPES: &[&str] = &[
+    "build", "ci", "docs", "feat", "fix", "perf", "refactor", "test",
+];
+
+/// Common gitmoji codes and what they stand for
+const GITMOJIS: &[(&str, &str)] = &[
+    (":sparkles:", "new feature"),
+    (":bug:", "bug fix"),
+    (":ambulance:", "critical hotfix"),
+    (":memo:", "documentation"),
+    (":art:", "structure or format of the code"),
+    (":zap:", "performance"),
+    (":fire:", "remove code or files"),
+    (":recycle:", "refactor"),
+    (":white_check_mark:", "tests"),
+    (":lock:", "security"),
+    (":arrow_up:", "upgrade dependencies"),
+    (":arrow_down:", "downgrade dependencies"),
+    (":construction_worker:", "CI build system"),
+    (":wrench:", "configuration files"),
+    (":lipstick:", "UI and styles"),
+    (":boom:", "breaking change"),
+    (":rewind:", "revert changes"),
+    (":truck:", "move or rename files"),
+    (":heavy_plus_sign:", "add a dependency"),
+    (":heavy_minus_sign:", "remove a dependency"),
+    (":pencil2:", "fix typos"),
+    (":rotating_light:", "fix compiler or linter warnings"),
+    (":bookmark:", "release or version tag"),
+    (":tada:", "begin a project"),
+];
+
+/// Case of the first letter of the subject
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubjectCase {
+    Lower,
+    Upper,
+}
+
+/// A message style: prompt instructions, validation rules and a formatter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageStyle {
+    pub name: String,
+    /// Extra instructions for the model, on top of those derived from the rules
+    pub instructions: Vec<String>,
+    /// Rules the formatted message is validated against
+    pub rules: CommitRules,
+    /// Force the case of the first letter of the subject
+    pub subject_case: Option<SubjectCase>,
+    /// Add (`true`) or strip (`false`) a period at the end of the subject
+    pub trailing_period: Option<bool>,
+}
+
+impl Default for MessageStyle {
+    fn default() -> Self {
+        Self {
+            name: "conventional".to_string(),
+            instructions: Vec::new(),
+            rules: CommitRules::default(),
+            subject_case: None,
+            trailing_period: None,
+        }
+    }
+}
+
+impl MessageStyle {
+    /// Look up a built-in preset by name
+    pub fn preset(name: &str) -> Option<Self> {
+        let base = Self {
+            name: name.to_string(),
+            ..Self::default()
+        };
+        let style = match name {
+            "conventional" => base,
+            "gitmoji" => Self {
+                instructions: vec![format!(
+                    "Pick the gitmoji that matches the change: {}",
+                    GITMOJIS
+                        .iter()
+                        .map(|(code, meaning)| format!("`{}` {}", code, meaning))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )],
+                rules: CommitRules {
+                    header: HeaderFormat::Gitmoji,
+                    types: GITMOJIS.iter().map(|(code, _)| code.to_string()).collect(),
+                    ..CommitRules::default()
+                },
+                subject_case: Some(SubjectCase::Upper),
+                trailing_period: Some(false),
+                ..base
+            },
+            "angular" => Self {
+                instructions: vec![
+                    "Start the subject with a lowercase letter and do not end it with a period"
+                        .to_string(),
+                    "Explain the motivation for the change in the body and contrast it with the previous behavior".to_string(),
+                ],
+                rules: CommitRules {
+                    types: ANGULAR_TYPES.iter().map(|t| t.to_string()).collect(),
+                    max_subject_length: 100,
+                    max_body_line_length: Some(100),
+                    ..CommitRules::default()
+                },
+                subject_case: Some(SubjectCase::Lower),
+                trailing_period: Some(false),
+                ..base
+            },
+            "kernel" => Self {
+                instructions: vec![
+                    "Describe the problem and why the change solves it in the body, in plain prose".to_string(),
+                ],
+                rules: CommitRules {
+                    header: HeaderFormat::Subsystem,
+                    types: Vec::new(),
+                    max_subject_length: 75,
+                    max_body_line_length: Some(75),
+                    ..CommitRules::default()
+                },
+                subject_case: Some(SubjectCase::Lower),
+                trailing_period: Some(false),
+                ..base
+            },
+            "plain-sentence" => Self {
+                rules: CommitRules {
+                    header: HeaderFormat::Plain,
+                    types: Vec::new(),
+                    ..CommitRules::default()
+                },
+                subject_case: Some(SubjectCase::Upper),
+                trailing_period: Some(false),
+                ..base
+            },
+            "one-line" => Self {
+                rules: CommitRules {
+                    allow_body: false,
+                    ..CommitRules::default()
+                },
+                ..base
+            },
+            _ => return None,
+        };
+        Some(style)
+    }
+
+    /// Fix the subject's case and period, and drop the body if not allowed
+    ///
+    /// Headers that do not match the style are left alone for the validator
+    /// to report.
+    pub fn format(&self, message: &str) -> String {
+        let (header, rest) = message.split_once('\n').unwrap_or((message, ""));
+        let Ok(parsed) = parse_styled_header(header, self.rules.header) else {
+            return message.to_string();
+        };
+
+        let prefix = &header[..header.len() - parsed.subject.len()];
+        let mut subject = parsed.subject.trim_end().to_string();
+        match self.trailing_period {
+            Some(true) if !subject.ends_with('.') => subject.push('.'),
+            Some(false) => subject = subject.trim_end_matches('.').to_string(),
+            _ => {}
+        }
+        if let Some(case) = self.subject_case {
+            subject = change_first_letter(&subject, case);
+        }
+
+        let header = format!("{}{}", prefix, subject);
+        if self.rules.allow_body && !rest.is_empty() {
+            format!("{}\n{}", header, rest)
+        } else {
+            header
+        }
+    }
+}
+
+/// Change the case of the first letter, leaving identifiers like `iOS` alone
+fn change_first_letter(subject: &str, case: SubjectCase) -> String {
+    let mut chars = subject.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    let second_is_upper = subject.chars().nth(1).is_some_and(|c| c.is_uppercase());
+    let first = match case {
+        SubjectCase::Upper => first.to_uppercase().to_string(),
+        SubjectCase::Lower if !second_is_upper => first.to_lowercase().to_string(),
+        SubjectCase::Lower => first.to_string(),
+    };
+    format!("{}{}", first, chars.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        for name in PRESETS {
+            assert_eq!(MessageStyle::preset(name).unwrap().name, *name);
+        }
+        assert!(MessageStyle::preset("unknown").is_none());
+    }
+
+    #[test]
+    fn test_format() {
+        let gitmoji = MessageStyle::preset("gitmoji").unwrap();
+        assert_eq!(
+            gitmoji.format(":bug: fix crash.\n\nDetails."),
+            ":bug: Fix crash\n\nDetails."
+        );
+
+        let angular = MessageStyle::preset("angular").unwrap();
+        assert_eq!(
+            angular.format("feat(ui): Add toggle"),
+            "feat(ui): add toggle"
+        );
+        assert_eq!(
+            angular.format("feat(ui): URL parsing"),
+            "feat(ui): URL parsing"
+        );
+
+        let one_line = MessageStyle::preset("one-line").unwrap();
+        assert_eq!(
+            one_line.format("fix: handle errors\n\nBody"),
+            "fix: handle errors"
+        );
+
+        let plain = MessageStyle::preset("plain-sentence").unwrap();
+        assert_eq!(plain.format("fix crash on start."), "Fix crash on start");
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::Context;
+use regex::Regex;
+
+use super::parser::{paragraphs, parse_footer_paragraph};
+use super::validator::{HeaderFormat, parse_styled_header};
+
+/// Default patterns: Jira-style keys (`PROJ-1234`) and GitHub issues (`#567`)
+pub const DEFAULT_TICKET_PATTERNS: &[&str] =
+    &[r"(?P<ticket>[A-Z][A-Z0-9]+-\d+)", r"(?P<ticket>#\d+)"];
+
+/// Default template for the subject placement
+pub const DEFAULT_SUBJECT_TEMPLATE: &str = "{ticket}";
+
+/// Default template for the footer placement
+pub const DEFAULT_FOOTER_TEMPLATE: &str = "Refs: {ticket}";
+
+/// Where the ticket reference is inserted into the message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketPlacement {
+    /// At the start of the subject, after `type(scope): ` or the style's prefix
+    Subject,
+    /// As a footer in the last paragraph
+    Footer,
+}
+
+/// Extracts ticket references from branch names and injects them into messages
+#[derive(Debug, Clone)]
+pub struct TicketReference {
+    patterns: Vec<Regex>,
+    placement: TicketPlacement,
+    template: String,
+}
+
+impl TicketReference {
+    /// Compile the branch patterns; `{ticket}` in the template is replaced by the reference
+    pub fn new(
+        patterns: &[String],
+        placement: TicketPlacement,
+        template: String,
+    ) -> anyhow::Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid ticket pattern: {}", p)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            patterns,
+            placement,
+            template,
+        })
+    }
+
+    /// Extract the ticket reference from a branch name
+    ///
+    /// Uses the `ticket` named group, the first capture group or the whole match.
+    pub fn extract(&self, branch: &str) -> Option<String> {
+        self.patterns.iter().find_map(|pattern| {
+            let captures = pattern.captures(branch)?;
+            captures
+                .name("ticket")
+                .or_else(|| captures.get(1))
+                .or_else(|| captures.get(0))
+                .map(|m| m.as_str().to_string())
+        })
+    }
+
+    /// Insert the ticket into the message unless it is already mentioned
+    ///
+    /// `header` is the format of the message's header line, so a subject
+    /// reference goes after its prefix.
+    pub fn apply(&self, message: &str, ticket: &str, header: HeaderFormat) -> String {
+        if mentions(message, ticket) {
+            return message.to_string();
+        }
+
+        let reference = self.template.replace("{ticket}", ticket);
+        match self.placement {
+            TicketPlacement::Subject => prefix_subject(message, &reference, header),
+            TicketPlacement::Footer => append_footer(message, &reference),
+        }
+    }
+
+    /// Characters the reference adds to the header line; 0 for footers
+    pub fn subject_overhead(&self, ticket: &str) -> usize {
+        match self.placement {
+            TicketPlacement::Subject => {
+                let reference = self.template.replace("{ticket}", ticket);
+                reference.chars().count() + separator(&reference).len()
+            }
+            TicketPlacement::Footer => 0,
+        }
+    }
+}
+
+/// Whether `ticket` appears as a whole word, so `PROJ-1` is not found in `PROJ-12`
+fn mentions(message: &str, ticket: &str) -> bool {
+    message.match_indices(ticket).any(|(start, _)| {
+        let before = message[..start].chars().next_back();
+        let after = message[start + ticket.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Space between the reference and the subject, unless the template ends with one
+fn separator(reference: &str) -> &'static str {
+    if reference.ends_with(char::is_whitespace) {
+        ""
+    } else {
+        " "
+    }
+}
+
+/// Prefix the subject, keeping any `type(scope): ` or style prefix in front
+fn prefix_subject(message: &str, reference: &str, format: HeaderFormat) -> String {
+    let (header, rest) = match message.split_once('\n') {
+        Some((header, rest)) => (header, Some(rest)),
+        None => (message, None),
+    };
+
+    let separator = separator(reference);
+    let header = match parse_styled_header(header, format) {
+        Ok(parsed) => {
+            let prefix = &header[..header.len() - parsed.subject.len()];
+            format!("{}{}{}{}", prefix, reference, separator, parsed.subject)
+        }
+        Err(_) => format!("{}{}{}", reference, separator, header),
+    };
+
+    match rest {
+        Some(rest) => format!("{}\n{}", header, rest),
+        None => header,
+    }
+}
+
+/// Add a footer line, joining an existing footer paragraph if there is one
+pub fn append_footer(message: &str, footer: &str) -> String {
+    let message = message.trim_end();
+    let rest = message.split_once('\n').map(|(_, rest)| rest).unwrap_or("");
+    let has_footers = paragraphs(rest)
+        .last()
+        .is_some_and(|last| parse_footer_paragraph(last).is_some());
+
+    if has_footers {
+        format!("{}\n{}", message, footer)
+    } else {
+        format!("{}\n\n{}", message, footer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(placement: TicketPlacement, template: &str) -> TicketReference {
+        let patterns: Vec<String> = DEFAULT_TICKET_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        TicketReference::new(&patterns, placement, template.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_extract() {
+        let reference = reference(TicketPlacement::Footer, DEFAULT_FOOTER_TEMPLATE);
+        assert_eq!(
+            reference.extract("feature/PROJ-1234-add-login").as_deref(),
+            Some("PROJ-1234")
+        );
+        assert_eq!(reference.extract("fix/#567").as_deref(), Some("#567"));
+        assert_eq!(reference.extract("main"), None);
+    }
+
+    #[test]
+    fn test_apply_footer() {
+        let reference = reference(TicketPlacement::Footer, DEFAULT_FOOTER_TEMPLATE);
+        let apply = |message: &str, ticket: &str| {
+            reference.apply(message, ticket, HeaderFormat::Conventional)
+        };
+        assert_eq!(
+            apply("feat: add login", "PROJ-1"),
+            "feat: add login\n\nRefs: PROJ-1"
+        );
+        assert_eq!(
+            apply("feat: add login\n\nCloses #2", "PROJ-1"),
+            "feat: add login\n\nCloses #2\nRefs: PROJ-1"
+        );
+        assert_eq!(
+            apply("feat: add login\n\nRefs: PROJ-1", "PROJ-1"),
+            "feat: add login\n\nRefs: PROJ-1"
+        );
+    }
+
+    #[test]
+    fn test_apply_with_longer_ticket_mentioned() {
+        let reference = reference(TicketPlacement::Footer, DEFAULT_FOOTER_TEMPLATE);
+        let apply = |message: &str, ticket: &str| {
+            reference.apply(message, ticket, HeaderFormat::Conventional)
+        };
+        assert_eq!(
+            apply("feat: add login\n\nRefs: PROJ-12", "PROJ-1"),
+            "feat: add login\n\nRefs: PROJ-12\nRefs: PROJ-1"
+        );
+        assert_eq!(
+            apply("fix: follow up on #567", "#5"),
+            "fix: follow up on #567\n\nRefs: #5"
+        );
+        assert_eq!(
+            apply("fix: follow up on #5.", "#5"),
+            "fix: follow up on #5."
+        );
+    }
+
+    #[test]
+    fn test_apply_subject() {
+        let reference = reference(TicketPlacement::Subject, DEFAULT_SUBJECT_TEMPLATE);
+        assert_eq!(
+            reference.apply(
+                "feat(auth): add login\n\nBody",
+                "PROJ-1",
+                HeaderFormat::Conventional
+            ),
+            "feat(auth): PROJ-1 add login\n\nBody"
+        );
+        assert_eq!(
+            reference.apply("Add login", "PROJ-1", HeaderFormat::Plain),
+            "PROJ-1 Add login"
+        );
+        assert_eq!(
+            reference.apply(":sparkles: Add login", "PROJ-1", HeaderFormat::Gitmoji),
+            ":sparkles: PROJ-1 Add login"
+        );
+        assert_eq!(
+            reference.apply("net: add login", "PROJ-1", HeaderFormat::Subsystem),
+            "net: PROJ-1 add login"
+        );
+        assert_eq!(reference.subject_overhead("PROJ-1"), "PROJ-1 ".len());
+    }
+}
@@ -0,0 +1,637 @@
+use std::fmt;
+
+use super::parser::{Header, ParseError, paragraphs, parse_footer_paragraph, parse_header};
+
+/// Commit types from the Conventional Commits / Angular convention
+pub const DEFAULT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Default maximum length of the header line
+pub const DEFAULT_MAX_SUBJECT_LENGTH: usize = 72;
+
+/// Trailer tokens that make a line at the end of the body a footer rather than prose
+const KNOWN_TRAILERS: &[&str] = &[
+    "BREAKING CHANGE",
+    "BREAKING-CHANGE",
+    "Signed-off-by",
+    "Co-authored-by",
+    "Reviewed-by",
+    "Acked-by",
+    "Tested-by",
+    "Reported-by",
+    "Refs",
+    "Closes",
+    "Fixes",
+    "Resolves",
+    "Change-Id",
+];
+
+/// Common verbs used to detect non-imperative subjects ("added", "adding", ...)
+const KNOWN_VERBS: &[&str] = &[
+    "add",
+    "adjust",
+    "allow",
+    "apply",
+    "avoid",
+    "bump",
+    "cache",
+    "change",
+    "check",
+    "clean",
+    "convert",
+    "correct",
+    "create",
+    "delete",
+    "deprecate",
+    "detect",
+    "disable",
+    "document",
+    "drop",
+    "enable",
+    "ensure",
+    "expose",
+    "extend",
+    "extract",
+    "fix",
+    "format",
+    "generate",
+    "handle",
+    "hide",
+    "implement",
+    "improve",
+    "increase",
+    "introduce",
+    "load",
+    "log",
+    "make",
+    "merge",
+    "migrate",
+    "move",
+    "optimize",
+    "parse",
+    "pass",
+    "prevent",
+    "print",
+    "read",
+    "reduce",
+    "refactor",
+    "release",
+    "remove",
+    "rename",
+    "reorganize",
+    "replace",
+    "resolve",
+    "restructure",
+    "return",
+    "revert",
+    "run",
+    "set",
+    "show",
+    "simplify",
+    "split",
+    "store",
+    "support",
+    "test",
+    "update",
+    "upgrade",
+    "use",
+    "validate",
+    "wrap",
+    "write",
+];
+
+/// Shape of the header line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeaderFormat {
+    /// `type(scope): subject`
+    #[default]
+    Conventional,
+    /// `:code: subject`, where the gitmoji code acts as the type
+    Gitmoji,
+    /// `subsystem: subject`, as in the Linux kernel
+    Subsystem,
+    /// A plain sentence without a prefix
+    Plain,
+}
+
+/// Rules a generated commit message must satisfy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitRules {
+    /// Shape of the header line
+    pub header: HeaderFormat,
+    /// Whether a body may follow the header
+    pub allow_body: bool,
+    /// Allowed commit types; empty allows any type
+    pub types: Vec<String>,
+    /// Allowed scopes; empty allows any scope
+    pub scopes: Vec<String>,
+    /// Whether every message must carry a scope
+    pub require_scope: bool,
+    /// Maximum length of the header line
+    pub max_subject_length: usize,
+    /// Maximum length of each body line, if limited
+    pub max_body_line_length: Option<usize>,
+    /// Whether the subject must use imperative mood
+    pub require_imperative: bool,
+    /// Trailer tokens recognized as footers besides the well-known ones
+    pub trailers: Vec<String>,
+}
+
+impl Default for CommitRules {
+    fn default() -> Self {
+        Self {
+            header: HeaderFormat::Conventional,
+            allow_body: true,
+            types: DEFAULT_TYPES.iter().map(|t| t.to_string()).collect(),
+            scopes: Vec::new(),
+            require_scope: false,
+            max_subject_length: DEFAULT_MAX_SUBJECT_LENGTH,
+            max_body_line_length: None,
+            require_imperative: true,
+            trailers: Vec::new(),
+        }
+    }
+}
+
+impl CommitRules {
+    /// Whether `token` is a known or configured trailer, ignoring case like git
+    fn is_trailer(&self, token: &str) -> bool {
+        KNOWN_TRAILERS
+            .iter()
+            .copied()
+            .chain(self.trailers.iter().map(String::as_str))
+            .any(|known| known.eq_ignore_ascii_case(token))
+    }
+
+    /// Describe the rules as extra instructions for the LLM prompt
+    pub fn prompt_instructions(&self) -> Vec<String> {
+        let format = match self.header {
+            HeaderFormat::Conventional => {
+                "Use the Conventional Commits header format `type(scope): subject`"
+            }
+            HeaderFormat::Gitmoji => {
+                "Start the header with a gitmoji code such as `:sparkles:`, followed by a space and the subject"
+            }
+            HeaderFormat::Subsystem => {
+                "Use the header format `subsystem: subject`, where the subsystem names the changed component or path, e.g. `net/ipv4`"
+            }
+            HeaderFormat::Plain => "Write the header as a plain sentence without any prefix",
+        };
+        let mut instructions = vec![format!(
+            "{} and keep the header at most {} characters",
+            format, self.max_subject_length
+        )];
+        match self.header {
+            HeaderFormat::Conventional if !self.types.is_empty() => instructions.push(format!(
+                "The type must be one of: {}",
+                self.types.join(", ")
+            )),
+            HeaderFormat::Gitmoji if !self.types.is_empty() => instructions.push(format!(
+                "The gitmoji must be one of: {}",
+                self.types.join(", ")
+            )),
+            _ => {}
+        }
+        if !self.allow_body {
+            instructions.push("Write only the header line, without a body".to_string());
+        }
+        // Scopes only exist in Conventional Commits headers
+        if self.header == HeaderFormat::Conventional {
+            match (self.require_scope, self.scopes.is_empty()) {
+                (true, true) => instructions.push("Always include a scope".to_string()),
+                (true, false) => instructions.push(format!(
+                    "Always include a scope, which must be one of: {}",
+                    self.scopes.join(", ")
+                )),
+                (false, false) => instructions.push(format!(
+                    "If a scope is used it must be one of: {}",
+                    self.scopes.join(", ")
+                )),
+                (false, true) => {}
+            }
+        }
+        if let Some(max) = self.max_body_line_length {
+            instructions.push(format!("Wrap body lines at {} characters", max));
+        }
+        if self.require_imperative {
+            instructions.push("Write the subject in imperative mood".to_string());
+        }
+        instructions
+    }
+}
+
+/// A single rule violation found in a commit message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    EmptyMessage,
+    InvalidHeader(String),
+    TypeNotAllowed {
+        found: String,
+        allowed: Vec<String>,
+    },
+    ScopeNotAllowed {
+        found: String,
+        allowed: Vec<String>,
+    },
+    MissingScope,
+    EmptySubject,
+    SubjectTooLong {
+        length: usize,
+        max: usize,
+    },
+    BodyLineTooLong {
+        line: usize,
+        length: usize,
+        max: usize,
+    },
+    NonImperativeSubject {
+        word: String,
+        suggestion: String,
+    },
+    MissingBlankLineAfterHeader,
+    MissingBlankLineBeforeFooter,
+    BodyNotAllowed,
+    WrongLanguage(String),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyMessage => write!(f, "commit message is empty"),
+            Self::InvalidHeader(reason) => write!(f, "invalid header: {}", reason),
+            Self::TypeNotAllowed { found, allowed } => write!(
+                f,
+                "type `{}` is not allowed (allowed: {})",
+                found,
+                allowed.join(", ")
+            ),
+            Self::ScopeNotAllowed { found, allowed } => write!(
+                f,
+                "scope `{}` is not allowed (allowed: {})",
+                found,
+                allowed.join(", ")
+            ),
+            Self::MissingScope => write!(f, "scope is required"),
+            Self::EmptySubject => write!(f, "subject is empty"),
+            Self::SubjectTooLong { length, max } => write!(
+                f,
+                "header is {} characters long, maximum is {}",
+                length, max
+            ),
+            Self::BodyLineTooLong { line, length, max } => write!(
+                f,
+                "line {} is {} characters long, maximum is {}",
+                line, length, max
+            ),
+            Self::NonImperativeSubject { word, suggestion } => write!(
+                f,
+                "subject should use imperative mood (`{}` instead of `{}`)",
+                suggestion, word
+            ),
+            Self::MissingBlankLineAfterHeader => {
+                write!(f, "header must be followed by a blank line")
+            }
+            Self::MissingBlankLineBeforeFooter => {
+                write!(f, "footers must be separated from the body by a blank line")
+            }
+            Self::BodyNotAllowed => write!(f, "message must be a single header line"),
+            Self::WrongLanguage(language) => {
+                write!(f, "subject and body must be written in {}", language)
+            }
+        }
+    }
+}
+
+/// Validate a commit message against the given rules
+pub fn validate(message: &str, rules: &CommitRules) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    let mut lines = message.lines();
+    let header_line = match lines.next() {
+        Some(line) if !line.trim().is_empty() => line,
+        _ => return vec![Violation::EmptyMessage],
+    };
+
+    let header_length = header_line.chars().count();
+    if header_length > rules.max_subject_length {
+        violations.push(Violation::SubjectTooLong {
+            length: header_length,
+            max: rules.max_subject_length,
+        });
+    }
+
+    match parse_styled_header(header_line, rules.header) {
+        Ok(header) => {
+            if !rules.types.is_empty()
+                && matches!(
+                    rules.header,
+                    HeaderFormat::Conventional | HeaderFormat::Gitmoji
+                )
+                && !rules.types.contains(&header.commit_type)
+            {
+                violations.push(Violation::TypeNotAllowed {
+                    found: header.commit_type.clone(),
+                    allowed: rules.types.clone(),
+                });
+            }
+            if rules.header == HeaderFormat::Conventional {
+                if rules.require_scope && header.scope.is_none() {
+                    violations.push(Violation::MissingScope);
+                }
+                if let Some(scope) = &header.scope
+                    && !rules.scopes.is_empty()
+                    && !rules.scopes.contains(scope)
+                {
+                    violations.push(Violation::ScopeNotAllowed {
+                        found: scope.clone(),
+                        allowed: rules.scopes.clone(),
+                    });
+                }
+            }
+            if header.subject.trim().is_empty() {
+                violations.push(Violation::EmptySubject);
+            } else if rules.require_imperative
+                && let Some((word, suggestion)) = non_imperative_word(&header.subject)
+            {
+                violations.push(Violation::NonImperativeSubject { word, suggestion });
+            }
+        }
+        Err(e) => violations.push(Violation::InvalidHeader(e.0)),
+    }
+
+    if !rules.allow_body && message.lines().skip(1).any(|line| !line.trim().is_empty()) {
+        violations.push(Violation::BodyNotAllowed);
+    }
+
+    if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+        violations.push(Violation::MissingBlankLineAfterHeader);
+    }
+
+    if let Some(max) = rules.max_body_line_length {
+        for (index, line) in message.lines().enumerate().skip(1) {
+            let length = line.chars().count();
+            if length > max {
+                violations.push(Violation::BodyLineTooLong {
+                    line: index + 1,
+                    length,
+                    max,
+                });
+            }
+        }
+    }
+
+    // A trailer glued to the end of a body paragraph means the blank line is
+    // missing; other `Word: text` lines such as "Note: ..." are prose
+    let rest = message.split_once('\n').map(|(_, rest)| rest).unwrap_or("");
+    if let Some(last) = paragraphs(rest).last()
+        && parse_footer_paragraph(last).is_none()
+        && let Some(split) = last
+            .iter()
+            .position(|line| parse_footer_paragraph(std::slice::from_ref(line)).is_some())
+        && split > 0
+        && let Some(footers) = parse_footer_paragraph(&last[split..])
+        && footers.iter().any(|footer| rules.is_trailer(&footer.token))
+    {
+        violations.push(Violation::MissingBlankLineBeforeFooter);
+    }
+
+    violations
+}
+
+/// Parse a header line in the given format
+///
+/// The gitmoji code or subsystem is reported as the type.
+pub fn parse_styled_header(line: &str, format: HeaderFormat) -> Result<Header, ParseError> {
+    let prefixed = |prefix: &str, subject: &str| Header {
+        commit_type: prefix.to_string(),
+        scope: None,
+        breaking: false,
+        subject: subject.to_string(),
+    };
+
+    match format {
+        HeaderFormat::Conventional => parse_header(line),
+        HeaderFormat::Gitmoji => {
+            let (code, subject) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseError("header must be `:gitmoji: subject`".into()))?;
+            let is_code = code.len() > 2
+                && code.starts_with(':')
+                && code.ends_with(':')
+                && code[1..code.len() - 1]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            let is_emoji = !code.is_empty() && code.chars().all(|c| !c.is_ascii());
+            if !is_code && !is_emoji {
+                return Err(ParseError(format!(
+                    "header must start with a gitmoji, found `{}`",
+                    code
+                )));
+            }
+            Ok(prefixed(code, subject.trim_start()))
+        }
+        HeaderFormat::Subsystem => {
+            let (subsystem, subject) = line
+                .split_once(": ")
+                .ok_or_else(|| ParseError("header must be `subsystem: subject`".into()))?;
+            if subsystem.is_empty() || subsystem.contains(char::is_whitespace) {
+                return Err(ParseError(format!(
+                    "`{}` is not a valid subsystem",
+                    subsystem
+                )));
+            }
+            Ok(prefixed(subsystem, subject))
+        }
+        HeaderFormat::Plain => Ok(prefixed("", line)),
+    }
+}
+
+/// Detect a non-imperative first word and suggest the imperative form
+///
+/// Only past tense and `-ing` forms are flagged; third-person forms such as
+/// "fixes" read the same as plural nouns like "tests" or "logs".
+fn non_imperative_word(subject: &str) -> Option<(String, String)> {
+    let word = subject.split_whitespace().next()?;
+    let lower = word.to_lowercase();
+    if KNOWN_VERBS.contains(&lower.as_str()) {
+        return None;
+    }
+
+    let mut candidates = Vec::new();
+    if let Some(stem) = lower.strip_suffix("ied") {
+        candidates.push(format!("{}y", stem));
+    }
+    if let Some(stem) = lower.strip_suffix("ed") {
+        candidates.push(stem.to_string());
+        candidates.push(format!("{}e", stem));
+        candidates.push(undouble(stem));
+    }
+    if let Some(stem) = lower.strip_suffix("ing") {
+        candidates.push(stem.to_string());
+        candidates.push(format!("{}e", stem));
+        candidates.push(undouble(stem));
+    }
+
+    candidates
+        .into_iter()
+        .find(|c| KNOWN_VERBS.contains(&c.as_str()))
+        .map(|suggestion| (word.to_string(), suggestion))
+}
+
+/// Remove a doubled final consonant, e.g. "dropp" -> "drop"
+fn undouble(stem: &str) -> String {
+    let mut chars: Vec<char> = stem.chars().collect();
+    if chars.len() >= 2 && chars[chars.len() - 1] == chars[chars.len() - 2] {
+        chars.pop();
+    }
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_message() {
+        let rules = CommitRules::default();
+        let violations = validate(
+            "feat(cli): add dry-run flag\n\nPrint the request instead of sending it.\n\nRefs: #12",
+            &rules,
+        );
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn test_type_scope_and_length() {
+        let rules = CommitRules {
+            scopes: vec!["core".to_string()],
+            max_subject_length: 20,
+            ..CommitRules::default()
+        };
+        let violations = validate("feature(cli): add a rather long subject", &rules);
+        assert!(matches!(violations[0], Violation::SubjectTooLong { .. }));
+        assert!(matches!(violations[1], Violation::TypeNotAllowed { .. }));
+        assert!(matches!(violations[2], Violation::ScopeNotAllowed { .. }));
+    }
+
+    #[test]
+    fn test_required_scope_and_body_lines() {
+        let rules = CommitRules {
+            require_scope: true,
+            max_body_line_length: Some(10),
+            ..CommitRules::default()
+        };
+        assert_eq!(
+            validate("fix: handle errors\n\nshort\nthis line is too long", &rules),
+            vec![
+                Violation::MissingScope,
+                Violation::BodyLineTooLong {
+                    line: 4,
+                    length: 21,
+                    max: 10
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_imperative_mood() {
+        let rules = CommitRules::default();
+        for (subject, expected) in [
+            ("fix: added retries", "add"),
+            ("fix: dropped support", "drop"),
+            ("fix: applied patch", "apply"),
+            ("fix: updating docs", "update"),
+        ] {
+            let violations = validate(subject, &rules);
+            assert_eq!(
+                violations,
+                vec![Violation::NonImperativeSubject {
+                    word: subject[5..].split(' ').next().unwrap().to_string(),
+                    suggestion: expected.to_string(),
+                }]
+            );
+        }
+        for subject in [
+            "fix: process queue",
+            "test: tests for the parser",
+            "chore: logs rotate daily",
+            "fix: updates no longer race",
+        ] {
+            assert!(validate(subject, &rules).is_empty(), "{}", subject);
+        }
+    }
+
+    #[test]
+    fn test_header_formats() {
+        let rules = CommitRules {
+            header: HeaderFormat::Gitmoji,
+            types: vec![":bug:".to_string()],
+            allow_body: false,
+            ..CommitRules::default()
+        };
+        assert!(validate(":bug: Fix crash on empty input", &rules).is_empty());
+        assert_eq!(
+            validate(":sparkles: Add login\n\nDetails", &rules),
+            vec![
+                Violation::TypeNotAllowed {
+                    found: ":sparkles:".to_string(),
+                    allowed: vec![":bug:".to_string()],
+                },
+                Violation::BodyNotAllowed,
+            ]
+        );
+        assert!(matches!(
+            validate("fix: crash", &rules)[0],
+            Violation::InvalidHeader(_)
+        ));
+
+        let rules = CommitRules {
+            header: HeaderFormat::Subsystem,
+            ..CommitRules::default()
+        };
+        assert!(validate("net/ipv4: fix checksum offload", &rules).is_empty());
+        assert!(matches!(
+            validate("fix the checksum offload", &rules)[0],
+            Violation::InvalidHeader(_)
+        ));
+    }
+
+    #[test]
+    fn test_blank_line_separation() {
+        let rules = CommitRules::default();
+        assert_eq!(
+            validate("fix: handle errors\nbody text", &rules),
+            vec![Violation::MissingBlankLineAfterHeader]
+        );
+        assert_eq!(
+            validate("fix: handle errors\n\nbody text\nRefs: #1", &rules),
+            vec![Violation::MissingBlankLineBeforeFooter]
+        );
+        assert_eq!(
+            validate("fix: handle errors\n\nbody text\nReviewed-by: Dev", &rules),
+            vec![Violation::MissingBlankLineBeforeFooter]
+        );
+        // Prose that happens to look like `Token: value`
+        assert!(validate("fix: handle errors\n\nRetry once.\nNote: slow", &rules).is_empty());
+        assert!(
+            validate(
+                "fix: handle errors\n\nRetry once.\nContext: flaky CI",
+                &rules
+            )
+            .is_empty()
+        );
+        let rules = CommitRules {
+            trailers: vec!["Context".to_string()],
+            ..rules
+        };
+        assert_eq!(
+            validate(
+                "fix: handle errors\n\nRetry once.\nContext: flaky CI",
+                &rules
+            ),
+            vec![Violation::MissingBlankLineBeforeFooter]
+        );
+    }
+}
@@ -0,0 +1,19 @@
+//! System prompts for features built on `LlmGenerater::complete`
+
+/// System prompt for grouping staged chunks into separate commits
+pub const SPLIT_PROMPT: &str = include_str!("split.md");
+
+/// System prompt for pull request titles and descriptions
+pub const PR_PROMPT: &str = include_str!("pr.md");
+
+/// System prompt for Keep a Changelog release notes
+pub const CHANGELOG_PROMPT: &str = include_str!("changelog.md");
+
+/// System prompt for annotated release tag messages
+pub const TAG_PROMPT: &str = include_str!("tag.md");
+
+/// System prompt for reviewing staged changes
+pub const REVIEW_PROMPT: &str = include_str!("review.md");
+
+/// System prompt for explaining existing commits
+pub const EXPLAIN_PROMPT: &str = include_str!("explain.md");
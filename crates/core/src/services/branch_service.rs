@@ -0,0 +1,163 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::branch::{BranchParts, DEFAULT_BRANCH_PATTERN, MAX_SLUG_LENGTH, render_branch, slugify};
+use crate::interfaces::git_repository::GitRepository;
+use crate::interfaces::llm_generater::{GenerationRequest, LlmGenerater};
+use crate::message::parser::parse_header;
+use crate::message::sanitizer::sanitize;
+use crate::message::scope::{ScopeMapping, detect_scopes, scope_instruction};
+use crate::message::ticket::TicketReference;
+use crate::message::validator::{CommitRules, HeaderFormat};
+
+/// A proposed branch name and the parts it was built from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchSuggestion {
+    pub name: String,
+    pub parts: BranchParts,
+}
+
+#[async_trait]
+pub trait BranchService {
+    /// Propose a branch name from a task description, or from the staged changes
+    async fn suggest(
+        &self,
+        description: Option<&str>,
+        ticket: Option<&str>,
+    ) -> anyhow::Result<BranchSuggestion>;
+
+    /// Create and switch to the branch
+    async fn create_branch(&self, name: &str) -> anyhow::Result<()>;
+}
+
+pub struct BranchServiceImpl {
+    llm_generater: Box<dyn LlmGenerater>,
+    git_repository: Box<dyn GitRepository>,
+    pattern: String,
+    rules: Option<CommitRules>,
+    scope_mappings: Vec<ScopeMapping>,
+    ticket: Option<TicketReference>,
+}
+
+impl BranchServiceImpl {
+    pub fn new(
+        llm_generater: Box<dyn LlmGenerater>,
+        git_repository: Box<dyn GitRepository>,
+    ) -> Self {
+        Self {
+            llm_generater,
+            git_repository,
+            pattern: DEFAULT_BRANCH_PATTERN.to_string(),
+            rules: None,
+            scope_mappings: Vec::new(),
+            ticket: None,
+        }
+    }
+
+    /// Pattern with `{type}`, `{scope}`, `{ticket}` and `{slug}` placeholders
+    pub fn with_pattern(mut self, pattern: String) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Restrict types and scopes like commit messages
+    pub fn with_rules(mut self, rules: Option<CommitRules>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Hint the model towards scopes derived from the staged file paths
+    pub fn with_scope_mappings(mut self, scope_mappings: Vec<ScopeMapping>) -> Self {
+        self.scope_mappings = scope_mappings;
+        self
+    }
+
+    /// Find ticket references in the task description
+    pub fn with_ticket(mut self, ticket: Option<TicketReference>) -> Self {
+        self.ticket = ticket;
+        self
+    }
+
+    /// Build the same request the commit message generation would send
+    async fn request(&self, description: Option<&str>) -> anyhow::Result<GenerationRequest> {
+        let mut request = match description {
+            Some(description) => {
+                let mut request = GenerationRequest::new(description.to_string());
+                request.instructions.push(
+                    "The input is a description of a planned task, not a diff. Write the commit header the finished work would get".to_string(),
+                );
+                request
+            }
+            None => {
+                let diff = self.git_repository.get_staged_diff().await?;
+                if diff.trim().is_empty() {
+                    return Err(anyhow!(
+                        "No staged changes found; describe the task instead"
+                    ));
+                }
+                let mut request = GenerationRequest::new(diff);
+                if !self.scope_mappings.is_empty() {
+                    let files = self.git_repository.get_staged_files().await?;
+                    let scopes = detect_scopes(&self.scope_mappings, &files);
+                    request.instructions.extend(scope_instruction(&scopes));
+                }
+                request
+            }
+        };
+
+        // Branch names need a Conventional Commits type, whatever style
+        // commit messages use
+        match &self.rules {
+            Some(rules) if rules.header == HeaderFormat::Conventional => {
+                request.instructions.extend(rules.prompt_instructions());
+            }
+            Some(_) => request
+                .instructions
+                .extend(CommitRules::default().prompt_instructions()),
+            None => {}
+        }
+        request.instructions.push(
+            "Return only the header line, without a body; it is used to name a branch".to_string(),
+        );
+        Ok(request)
+    }
+}
+
+#[async_trait]
+impl BranchService for BranchServiceImpl {
+    async fn suggest(
+        &self,
+        description: Option<&str>,
+        ticket: Option<&str>,
+    ) -> anyhow::Result<BranchSuggestion> {
+        let request = self.request(description).await?;
+        let message = sanitize(&self.llm_generater.generate_commit_message(request).await?);
+        let subject = message.lines().next().unwrap_or_default();
+        let header = parse_header(subject)
+            .map_err(|e| anyhow!("The model did not return a commit header: {}", e))?;
+
+        let ticket = ticket.map(str::to_string).or_else(|| {
+            let reference = self.ticket.as_ref()?;
+            reference.extract(description?)
+        });
+        let parts = BranchParts {
+            commit_type: header.commit_type,
+            scope: header.scope,
+            ticket,
+            slug: slugify(&header.subject, MAX_SLUG_LENGTH),
+        };
+
+        let name = render_branch(&self.pattern, &parts);
+        if name.is_empty() {
+            return Err(anyhow!(
+                "Pattern {} produced an empty branch name",
+                self.pattern
+            ));
+        }
+        Ok(BranchSuggestion { name, parts })
+    }
+
+    async fn create_branch(&self, name: &str) -> anyhow::Result<()> {
+        self.git_repository.create_branch(name).await
+    }
+}
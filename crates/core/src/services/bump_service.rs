@@ -0,0 +1,123 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::interfaces::git_repository::{CommitInfo, GitRepository};
+use crate::interfaces::llm_generater::LlmGenerater;
+use crate::message::sanitizer::sanitize;
+use crate::prompts::TAG_PROMPT;
+use crate::version::{Bump, BumpReason, Version, api_reasons, commit_reasons};
+
+/// Version used as the base when the repository has no tags yet
+const INITIAL_VERSION: &str = "v0.0.0";
+
+/// A recommended version bump and why
+#[derive(Debug, Clone)]
+pub struct BumpRecommendation {
+    /// Latest tag, or `None` if the repository has no tags
+    pub current: Option<String>,
+    pub bump: Bump,
+    /// Tag name of the next version, keeping the current tag's prefix
+    pub next: String,
+    /// Reasons sorted from the most significant
+    pub reasons: Vec<BumpReason>,
+    pub commits: Vec<CommitInfo>,
+}
+
+#[async_trait]
+pub trait BumpService {
+    /// Recommend the next version from commits and API changes since the last tag
+    async fn recommend(&self) -> anyhow::Result<BumpRecommendation>;
+
+    /// Generate the annotated tag message for the recommended release
+    async fn tag_message(&self, recommendation: &BumpRecommendation) -> anyhow::Result<String>;
+
+    /// Create the annotated tag on HEAD
+    async fn create_tag(&self, name: &str, message: &str) -> anyhow::Result<()>;
+}
+
+pub struct BumpServiceImpl {
+    llm_generater: Box<dyn LlmGenerater>,
+    git_repository: Box<dyn GitRepository>,
+}
+
+impl BumpServiceImpl {
+    pub fn new(
+        llm_generater: Box<dyn LlmGenerater>,
+        git_repository: Box<dyn GitRepository>,
+    ) -> Self {
+        Self {
+            llm_generater,
+            git_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl BumpService for BumpServiceImpl {
+    async fn recommend(&self) -> anyhow::Result<BumpRecommendation> {
+        let current = self.git_repository.latest_tag().await?;
+        let version = match &current {
+            Some(tag) => Version::parse(tag)
+                .ok_or_else(|| anyhow!("Latest tag {} is not a semantic version", tag))?,
+            None => Version::parse(INITIAL_VERSION).expect("valid initial version"),
+        };
+
+        let commits = match &current {
+            Some(tag) => self.git_repository.log(&format!("{}..HEAD", tag)).await?,
+            None => self.git_repository.log("HEAD").await?,
+        };
+        if commits.is_empty() {
+            return Err(anyhow!(
+                "No commits since {}",
+                current.as_deref().unwrap_or("the start of history")
+            ));
+        }
+
+        let mut reasons = commit_reasons(&commits);
+        // Without a previous release there is no public API to break
+        if let Some(tag) = &current {
+            let diff = self
+                .git_repository
+                .diff_range(&format!("{}..HEAD", tag))
+                .await?;
+            reasons.extend(api_reasons(&diff));
+        }
+        // Stable sort keeps commit order within each bump level
+        reasons.sort_by_key(|reason| std::cmp::Reverse(reason.bump));
+
+        let bump = reasons
+            .first()
+            .map(|reason| reason.bump)
+            .unwrap_or(Bump::Patch);
+
+        Ok(BumpRecommendation {
+            current,
+            bump,
+            next: version.bump(bump).to_string(),
+            reasons,
+            commits,
+        })
+    }
+
+    async fn tag_message(&self, recommendation: &BumpRecommendation) -> anyhow::Result<String> {
+        let mut content = format!(
+            "Release: {}\nBump: {}\n\n## Reasons\n\n",
+            recommendation.next,
+            recommendation.bump.as_str()
+        );
+        for reason in &recommendation.reasons {
+            content.push_str(&format!("- {}\n", reason.description));
+        }
+        content.push_str("\n## Commits\n\n");
+        for commit in &recommendation.commits {
+            content.push_str(&format!("- {}\n", commit.subject));
+        }
+
+        let message = self.llm_generater.complete(TAG_PROMPT, content).await?;
+        Ok(sanitize(&message))
+    }
+
+    async fn create_tag(&self, name: &str, message: &str) -> anyhow::Result<()> {
+        self.git_repository.create_tag(name, message).await
+    }
+}
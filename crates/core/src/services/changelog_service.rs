@@ -0,0 +1,125 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::changelog::{ChangeCategory, Classification, classify, release_heading};
+use crate::interfaces::git_repository::{CommitInfo, GitRepository};
+use crate::interfaces::llm_generater::LlmGenerater;
+use crate::message::sanitizer::strip_code_fence;
+use crate::prompts::CHANGELOG_PROMPT;
+
+#[async_trait]
+pub trait ChangelogService {
+    /// Generate the changelog section for the commits in `range`
+    async fn generate(&self, range: &str, version: &str) -> anyhow::Result<String>;
+}
+
+pub struct ChangelogServiceImpl {
+    llm_generater: Box<dyn LlmGenerater>,
+    git_repository: Box<dyn GitRepository>,
+    date: Option<String>,
+}
+
+impl ChangelogServiceImpl {
+    pub fn new(
+        llm_generater: Box<dyn LlmGenerater>,
+        git_repository: Box<dyn GitRepository>,
+    ) -> Self {
+        Self {
+            llm_generater,
+            git_repository,
+            date: None,
+        }
+    }
+
+    /// Date releases other than `Unreleased` with the release day, `YYYY-MM-DD`
+    pub fn with_date(mut self, date: Option<String>) -> Self {
+        self.date = date;
+        self
+    }
+}
+
+#[async_trait]
+impl ChangelogService for ChangelogServiceImpl {
+    async fn generate(&self, range: &str, version: &str) -> anyhow::Result<String> {
+        let commits = self.git_repository.log(range).await?;
+        if commits.is_empty() {
+            return Err(anyhow!("No commits found in {}", range));
+        }
+
+        let notes = self
+            .llm_generater
+            .complete(CHANGELOG_PROMPT, describe_commits(&commits))
+            .await?;
+        let notes = strip_code_fence(notes.trim());
+
+        Ok(format!(
+            "{}\n\n{}",
+            release_heading(version, self.date.as_deref()),
+            notes.trim()
+        ))
+    }
+}
+
+/// Group commits by category for the prompt, dropping internal ones
+fn describe_commits(commits: &[CommitInfo]) -> String {
+    let mut groups: Vec<(&str, Vec<&CommitInfo>)> = ChangeCategory::ALL
+        .iter()
+        .map(|category| (category.as_str(), Vec::new()))
+        .collect();
+    groups.push(("Reverted", Vec::new()));
+    groups.push(("Unclassified", Vec::new()));
+
+    for commit in commits {
+        let group = match classify(commit) {
+            Classification::Category(category) => category.as_str(),
+            Classification::Internal => continue,
+            Classification::Revert => "Reverted",
+            Classification::Unknown => "Unclassified",
+        };
+        if let Some((_, entries)) = groups.iter_mut().find(|(name, _)| *name == group) {
+            entries.push(commit);
+        }
+    }
+
+    let mut text = String::new();
+    for (name, entries) in groups.iter().filter(|(_, entries)| !entries.is_empty()) {
+        text.push_str(&format!("## {}\n\n", name));
+        for commit in entries {
+            text.push_str(&format!("- {}\n", commit.subject));
+            for line in commit.body.lines().filter(|l| !l.trim().is_empty()) {
+                text.push_str(&format!("  {}\n", line));
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(subject: &str) -> CommitInfo {
+        CommitInfo {
+            hash: "abc123".to_string(),
+            date: "2024-05-01".to_string(),
+            subject: subject.to_string(),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_describe_commits() {
+        let text = describe_commits(&[
+            commit("fix: handle empty input"),
+            commit("feat: add login"),
+            commit("ci: cache cargo"),
+            commit("revert: add export"),
+            commit("Fix stuff"),
+        ]);
+        assert_eq!(
+            text,
+            "## Added\n\n- feat: add login\n\n## Fixed\n\n- fix: handle empty input\n\n## Reverted\n\n- revert: add export\n\n## Unclassified\n\n- Fix stuff\n\n"
+        );
+    }
+}
@@ -1,20 +1,67 @@
+use anyhow::anyhow;
 use async_trait::async_trait;
 
-use crate::interfaces::git_repository::GitRepository;
-use crate::interfaces::llm_generater::LlmGenerater;
+use crate::context::{ContextOptions, FileContext, render_context};
+use crate::diff::ChangeKind;
+use crate::interfaces::git_repository::{
+    CommitInfo, CommitOptions, GitRepository, MergeKind, MergeState,
+};
+use crate::interfaces::llm_generater::{GenerationRequest, LlmGenerater, RequestPreview};
+use crate::message::language::{is_in_language, language_instruction, language_name};
+use crate::message::parser::Footer;
+use crate::message::sanitizer::sanitize;
+use crate::message::scope::{ScopeMapping, detect_scopes, scope_instruction};
+use crate::message::style::MessageStyle;
+use crate::message::ticket::TicketReference;
+use crate::message::validator::{CommitRules, HeaderFormat, Violation, validate};
+
+/// Tells the model that the extra context is not part of the change
+const CONTEXT_INSTRUCTION: &str = "The diff may show whole functions as context and the full contents of small changed files. Describe only the added and removed lines";
+
+/// Default number of times the model is re-prompted with validation errors
+pub const DEFAULT_MAX_RETRIES: usize = 2;
 
 #[async_trait]
 pub trait CommitService {
     /// Generate and optionally apply commit message
     async fn commit(&self, apply: bool) -> anyhow::Result<String>;
 
+    /// Describe the first request `commit` would send, without sending it
+    async fn preview(&self) -> anyhow::Result<RequestPreview>;
+
+    /// Validate a message drafted elsewhere, e.g. in a split plan, like a
+    /// generated one: re-prompt with `diff` while it breaks the rules, then add
+    /// the ticket reference and trailers
+    async fn finish_message(&self, diff: String, draft: String) -> anyhow::Result<String>;
+
     /// Apply a commit with the given message
+    ///
+    /// If the commit fails the message is saved, see `saved_message`.
     async fn apply_commit(&self, message: &str) -> anyhow::Result<()>;
+
+    /// Message of the last commit that failed to apply, if any
+    async fn saved_message(&self) -> anyhow::Result<Option<String>>;
+
+    /// Generate one message describing all commits in `range`
+    async fn squash_message(&self, range: &str) -> anyhow::Result<String>;
+
+    /// Replace the commits after `base` with a single commit
+    async fn apply_squash(&self, base: &str, message: &str) -> anyhow::Result<()>;
 }
 
 pub struct CommitServiceImpl {
     llm_generater: Box<dyn LlmGenerater>,
     git_repository: Box<dyn GitRepository>,
+    rules: Option<CommitRules>,
+    max_retries: usize,
+    scope_mappings: Vec<ScopeMapping>,
+    ticket: Option<TicketReference>,
+    sign_off: bool,
+    trailers: Vec<Footer>,
+    commit_options: CommitOptions,
+    language: Option<String>,
+    style: MessageStyle,
+    context: ContextOptions,
 }
 
 impl CommitServiceImpl {
@@ -25,17 +72,384 @@ impl CommitServiceImpl {
         Self {
             llm_generater,
             git_repository,
+            rules: Some(CommitRules::default()),
+            max_retries: DEFAULT_MAX_RETRIES,
+            scope_mappings: Vec::new(),
+            ticket: None,
+            sign_off: false,
+            trailers: Vec::new(),
+            commit_options: CommitOptions::default(),
+            language: None,
+            style: MessageStyle::default(),
+            context: ContextOptions::default(),
         }
     }
 
+    /// Validate generated messages against the given rules, or disable validation with `None`
+    pub fn with_rules(mut self, rules: Option<CommitRules>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Set how many times the model is re-prompted when validation fails
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Hint the model towards scopes derived from the staged file paths
+    pub fn with_scope_mappings(mut self, scope_mappings: Vec<ScopeMapping>) -> Self {
+        self.scope_mappings = scope_mappings;
+        self
+    }
+
+    /// Insert the ticket reference found in the branch name into generated messages
+    pub fn with_ticket(mut self, ticket: Option<TicketReference>) -> Self {
+        self.ticket = ticket;
+        self
+    }
+
+    /// Add a `Signed-off-by` trailer for the committer
+    pub fn with_sign_off(mut self, sign_off: bool) -> Self {
+        self.sign_off = sign_off;
+        self
+    }
+
+    /// Add trailers such as `Co-authored-by` to generated messages
+    pub fn with_trailers(mut self, trailers: Vec<Footer>) -> Self {
+        self.trailers = trailers;
+        self
+    }
+
+    /// Describe and format messages in the given style
+    ///
+    /// Validation still uses the rules from [`Self::with_rules`].
+    pub fn with_style(mut self, style: MessageStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Send enclosing functions and small files along with the diff
+    pub fn with_context(mut self, context: ContextOptions) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Write subjects and bodies in the given language, e.g. `zh-TW`
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Set the options passed to `git commit` when applying
+    pub fn with_commit_options(mut self, commit_options: CommitOptions) -> Self {
+        self.commit_options = commit_options;
+        self
+    }
+
     /// Internal helper to generate commit message from staged changes
     async fn generate_commit_message(&self) -> anyhow::Result<String> {
-        // 1. Get git diff
-        let diff = self.git_repository.get_staged_diff().await?;
+        let (request, rules) = self.commit_request().await?;
+        let message = self
+            .generate_validated_message(request, rules, None)
+            .await?;
+        self.finalize_message(message).await
+    }
+
+    /// Build the request describing the staged changes and the rules to validate against
+    async fn commit_request(&self) -> anyhow::Result<(GenerationRequest, Option<&CommitRules>)> {
+        // 1. Get git diff, with surrounding code if requested
+        let mut request = if self.context.is_enabled() {
+            let mut request = GenerationRequest::new(self.staged_context().await?);
+            request.instructions.push(CONTEXT_INSTRUCTION.to_string());
+            request
+        } else {
+            GenerationRequest::new(self.git_repository.get_staged_diff().await?)
+        };
+        let mut rules = self.rules.as_ref();
+
+        // 2. Summarize merges instead of describing the diff
+        match self.git_repository.merge_state().await? {
+            Some(state) if state.kind == MergeKind::Merge => {
+                request.instructions.push(merge_instruction(&state));
+                // Like commitlint, leave merge commits to git's own format
+                rules = None;
+            }
+            Some(state) => request.instructions.push(squash_instruction(
+                state.incoming.as_deref(),
+                &state.commits,
+            )),
+            None => {}
+        }
+
+        // 3. Hint at scopes derived from the staged paths
+        if !self.scope_mappings.is_empty() {
+            let files = self.git_repository.get_staged_files().await?;
+            let scopes = detect_scopes(&self.scope_mappings, &files);
+            request.instructions.extend(scope_instruction(&scopes));
+        }
+
+        Ok((request, rules))
+    }
+
+    /// Render the staged changes with the configured extra context
+    async fn staged_context(&self) -> anyhow::Result<String> {
+        let changes = self.git_repository.get_staged_changes().await?;
+        if changes.is_empty() {
+            return Err(anyhow!("No staged changes found"));
+        }
+        let mut function_patches = if self.context.function_context {
+            self.git_repository.get_staged_function_patches().await?
+        } else {
+            Vec::new()
+        };
 
-        // 2. Generate commit message using LLM
-        self.llm_generater.generate_commit_message(diff).await
+        let mut files = Vec::new();
+        for change in changes.files {
+            let function_patch = function_patches
+                .iter()
+                .position(|p| p.path == change.new_path)
+                .map(|i| function_patches.swap_remove(i));
+            let contents = if self.context.full_file_lines > 0
+                && change.kind != ChangeKind::Deleted
+                && !change.binary
+            {
+                self.git_repository
+                    .get_staged_file(&change.new_path)
+                    .await?
+                    .filter(|c| c.lines().count() <= self.context.full_file_lines)
+            } else {
+                None
+            };
+            files.push(FileContext {
+                change,
+                function_patch,
+                contents,
+            });
+        }
+        Ok(render_context(&files, self.context.budget))
     }
+
+    /// Fail unless `base` is in the history of `tip`
+    ///
+    /// Commits reachable from `base` but not `tip` would be silently dropped
+    /// by a squash.
+    async fn ensure_ancestor(&self, base: &str, tip: &str) -> anyhow::Result<()> {
+        if !self
+            .git_repository
+            .log(&format!("{}..{}", tip, base))
+            .await?
+            .is_empty()
+        {
+            return Err(anyhow!(
+                "{} is not an ancestor of {}; rebase onto it first",
+                base,
+                tip
+            ));
+        }
+        Ok(())
+    }
+
+    /// Add the ticket reference and trailers to a generated message
+    async fn finalize_message(&self, mut message: String) -> anyhow::Result<String> {
+        // Inject the ticket after validation since the model cannot influence it;
+        // its length was reserved in the header limit
+        if let Some(ticket) = &self.ticket
+            && let Some(reference) = self.ticket_reference().await?
+        {
+            message = ticket.apply(&message, &reference, self.header_format());
+        }
+
+        let mut trailers = self.trailers.clone();
+        if self.sign_off {
+            trailers.push(Footer {
+                token: "Signed-off-by".to_string(),
+                value: self.git_repository.committer_identity().await?,
+            });
+        }
+        if !trailers.is_empty() {
+            message = self
+                .git_repository
+                .add_trailers(&message, &trailers)
+                .await?;
+        }
+
+        Ok(message)
+    }
+
+    /// Ticket reference of the current branch, if tickets are configured
+    async fn ticket_reference(&self) -> anyhow::Result<Option<String>> {
+        let Some(ticket) = &self.ticket else {
+            return Ok(None);
+        };
+        Ok(self
+            .git_repository
+            .current_branch()
+            .await?
+            .and_then(|branch| ticket.extract(&branch)))
+    }
+
+    /// Header format of generated messages, for placing the ticket
+    fn header_format(&self) -> HeaderFormat {
+        self.rules
+            .as_ref()
+            .map_or(self.style.rules.header, |rules| rules.header)
+    }
+
+    /// Describe the style, rules and language to the model up front
+    fn add_message_instructions(
+        &self,
+        request: &mut GenerationRequest,
+        rules: Option<&CommitRules>,
+    ) {
+        request
+            .instructions
+            .extend(self.style.instructions.iter().cloned());
+        if let Some(rules) = rules {
+            request.instructions.extend(rules.prompt_instructions());
+        }
+        if let Some(language) = &self.language {
+            request.instructions.push(language_instruction(language));
+        }
+    }
+
+    /// Generate a message, or start from `draft`, and re-prompt until it passes validation
+    async fn generate_validated_message(
+        &self,
+        mut request: GenerationRequest,
+        rules: Option<&CommitRules>,
+        mut draft: Option<String>,
+    ) -> anyhow::Result<String> {
+        let overhead = match (&self.ticket, self.ticket_reference().await?) {
+            (Some(ticket), Some(reference)) => ticket.subject_overhead(&reference),
+            _ => 0,
+        };
+        // Leave room in the header for a ticket added to the subject afterwards,
+        // and treat the configured trailers as footers
+        let adjusted = rules.map(|rules| CommitRules {
+            max_subject_length: rules.max_subject_length.saturating_sub(overhead),
+            trailers: (rules.trailers.iter().cloned())
+                .chain(self.trailers.iter().map(|t| t.token.clone()))
+                .collect(),
+            ..rules.clone()
+        });
+        let rules = adjusted.as_ref();
+
+        self.add_message_instructions(&mut request, rules);
+        let base_instructions = request.instructions.clone();
+
+        // Generate, repair and validate, re-prompting with the errors
+        let mut attempt = 0;
+        loop {
+            let raw = match draft.take() {
+                Some(draft) => draft,
+                None => {
+                    self.llm_generater
+                        .generate_commit_message(request.clone())
+                        .await?
+                }
+            };
+            let message = self.style.format(&sanitize(&raw));
+
+            let mut violations = rules.map(|r| validate(&message, r)).unwrap_or_default();
+            if let Some(language) = &self.language
+                && !is_in_language(&message, language)
+            {
+                violations.push(Violation::WrongLanguage(language_name(language)));
+            }
+            if violations.is_empty() {
+                return Ok(message);
+            }
+            // Never hand back a message that breaks the rules without saying so
+            if attempt >= self.max_retries {
+                return Err(anyhow!(
+                    "The message still breaks the rules after {} retries:\n{}\n\nLast attempt:\n{}",
+                    self.max_retries,
+                    problem_list(&violations),
+                    message
+                ));
+            }
+
+            attempt += 1;
+            request.instructions = base_instructions.clone();
+            request
+                .instructions
+                .push(feedback_instruction(&message, &violations));
+        }
+    }
+}
+
+/// List commits as `- subject` lines for the prompt
+fn commit_list(commits: &[CommitInfo]) -> String {
+    commits
+        .iter()
+        .map(|c| format!("- {}", c.subject))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build the instruction for concluding a merge
+fn merge_instruction(state: &MergeState) -> String {
+    let subject = match &state.incoming {
+        Some(incoming) => format!("Merge branch '{}'", incoming),
+        None => "Merge".to_string(),
+    };
+    let mut instruction = format!(
+        "This commit concludes a merge. Start the subject with `{}` and write a body that summarizes what the merged commits bring in rather than describing the diff line by line",
+        subject
+    );
+    if !state.commits.is_empty() {
+        instruction.push_str(&format!(
+            ".\n\nMerged commits:\n{}",
+            commit_list(&state.commits)
+        ));
+    }
+    if !state.conflicts.is_empty() {
+        instruction.push_str(&format!(
+            "\n\nConflicts were resolved in these files; explain how in the body:\n{}",
+            state
+                .conflicts
+                .iter()
+                .map(|f| format!("- {}", f))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+    instruction
+}
+
+/// Build the instruction for condensing several commits into one
+fn squash_instruction(incoming: Option<&str>, commits: &[CommitInfo]) -> String {
+    let source = match incoming {
+        Some(incoming) => format!(" from `{}`", incoming),
+        None => String::new(),
+    };
+    let mut instruction = format!(
+        "These changes squash several commits{} into one. Write a single message describing their combined effect; do not list the individual commits or mention fixups",
+        source
+    );
+    if !commits.is_empty() {
+        instruction.push_str(&format!(".\n\nSquashed commits:\n{}", commit_list(commits)));
+    }
+    instruction
+}
+
+/// List violations as `- problem` lines
+fn problem_list(violations: &[Violation]) -> String {
+    violations
+        .iter()
+        .map(|v| format!("- {}", v))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build the instruction that tells the model what was wrong with its last attempt
+fn feedback_instruction(message: &str, violations: &[Violation]) -> String {
+    format!(
+        "Your previous commit message was rejected:\n\n{}\n\nProblems:\n{}\n\nWrite a corrected commit message that fixes all of these problems.",
+        message,
+        problem_list(violations)
+    )
 }
 
 #[async_trait]
@@ -46,13 +460,374 @@ impl CommitService for CommitServiceImpl {
 
         // 2. Optionally apply the commit
         if apply {
-            self.git_repository.commit(&message).await?;
+            self.apply_commit(&message).await?;
         }
 
         Ok(message)
     }
 
+    async fn preview(&self) -> anyhow::Result<RequestPreview> {
+        let (mut request, rules) = self.commit_request().await?;
+        self.add_message_instructions(&mut request, rules);
+        Ok(self.llm_generater.preview_commit_message(request))
+    }
+
+    async fn finish_message(&self, diff: String, draft: String) -> anyhow::Result<String> {
+        let message = self
+            .generate_validated_message(
+                GenerationRequest::new(diff),
+                self.rules.as_ref(),
+                Some(draft),
+            )
+            .await?;
+        self.finalize_message(message).await
+    }
+
     async fn apply_commit(&self, message: &str) -> anyhow::Result<()> {
-        self.git_repository.commit(message).await
+        if let Err(e) = self
+            .git_repository
+            .commit(message, &self.commit_options)
+            .await
+        {
+            // Hooks, signing or a changed index can fail the commit; keep the message for a retry
+            if let Err(save_error) = self.git_repository.save_message(message).await {
+                return Err(anyhow!(
+                    "{}\nThe message could not be saved: {}",
+                    e,
+                    save_error
+                ));
+            }
+            return Err(e);
+        }
+
+        // The commit exists, so a leftover message is only stale and not worth failing over
+        let _ = self.git_repository.clear_saved_message().await;
+        Ok(())
+    }
+
+    async fn saved_message(&self) -> anyhow::Result<Option<String>> {
+        self.git_repository.saved_message().await
+    }
+
+    async fn squash_message(&self, range: &str) -> anyhow::Result<String> {
+        let (base, tip) = match range.split_once("..") {
+            Some((base, tip)) if !base.is_empty() && !tip.starts_with('.') => {
+                (base, if tip.is_empty() { "HEAD" } else { tip })
+            }
+            _ => return Err(anyhow!("Range {} must have the form <base>..<tip>", range)),
+        };
+        // Check before asking the model, not only when applying
+        self.ensure_ancestor(base, tip).await?;
+
+        let commits = self.git_repository.log(range).await?;
+        if commits.is_empty() {
+            return Err(anyhow!("No commits found in {}", range));
+        }
+
+        // Diff from the merge base so changes on the base branch do not show up reversed
+        let diff = self
+            .git_repository
+            .diff_range(&format!("{}...{}", base, tip))
+            .await?;
+        let mut request = GenerationRequest::new(diff);
+        request
+            .instructions
+            .push(squash_instruction(None, &commits));
+
+        let message = self
+            .generate_validated_message(request, self.rules.as_ref(), None)
+            .await?;
+        self.finalize_message(message).await
+    }
+
+    async fn apply_squash(&self, base: &str, message: &str) -> anyhow::Result<()> {
+        // Soft reset would fold staged changes into the squashed commit
+        if !self.git_repository.get_staged_files().await?.is_empty() {
+            return Err(anyhow!(
+                "Staged changes would end up in the squashed commit; commit or unstage them first"
+            ));
+        }
+        self.ensure_ancestor(base, "HEAD").await?;
+
+        self.git_repository.soft_reset(base).await?;
+        self.git_repository
+            .commit(message, &self.commit_options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FakeGitRepository, ScriptedLlm, commit_info, file_patch, hunk};
+
+    /// The staged `src/lib.rs` of a `feature/PROJ-42-parser` branch
+    fn git() -> FakeGitRepository {
+        FakeGitRepository {
+            commits: vec![
+                commit_info("feat: add parser"),
+                commit_info("fixup! feat: add parser"),
+            ],
+            range_diff: "diff --git a/src/parser.rs b/src/parser.rs".to_string(),
+            branch: Some("feature/PROJ-42-parser".to_string()),
+            ..FakeGitRepository::staging(vec![file_patch(
+                "src/lib.rs",
+                vec![hunk("@@ -1 +1 @@", "+fn parse() {}")],
+            )])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sanitizes_fenced_output() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["```\nfeat: add parser\n```"]));
+        let service = CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git()));
+
+        assert_eq!(service.commit(false).await.unwrap(), "feat: add parser");
+        assert_eq!(llm.requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_preview_does_not_generate() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&[]));
+        let service = CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git()))
+            .with_language(Some("ja".to_string()));
+
+        service.preview().await.unwrap();
+
+        assert!(llm.requests.lock().unwrap().is_empty());
+        let previews = llm.previews.lock().unwrap();
+        assert_eq!(previews.len(), 1);
+        assert!(
+            previews[0]
+                .instructions
+                .iter()
+                .any(|i| i.contains("Japanese"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_finish_message_validates_draft() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["feat: add parser"]));
+        let service =
+            CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git())).with_sign_off(true);
+
+        let message = service
+            .finish_message(
+                "diff --git a/src/parser.rs".to_string(),
+                "Added parser".to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            message,
+            "feat: add parser\n\nSigned-off-by: Dev <dev@example.com>"
+        );
+
+        let requests = llm.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].diff, "diff --git a/src/parser.rs");
+        assert!(
+            requests[0]
+                .instructions
+                .last()
+                .unwrap()
+                .contains("Added parser")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_saves_message_when_commit_fails() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["feat: add parser"]));
+        let service = CommitServiceImpl::new(
+            Box::new(llm.clone()),
+            Box::new(FakeGitRepository {
+                fail_commit: true,
+                ..git()
+            }),
+        );
+
+        assert!(service.commit(true).await.is_err());
+        assert_eq!(
+            service.saved_message().await.unwrap().as_deref(),
+            Some("feat: add parser")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scope_hint() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["feat(core): add parser"]));
+        let service = CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git()))
+            .with_scope_mappings(vec![ScopeMapping::new("src/**", "core")]);
+
+        service.commit(false).await.unwrap();
+
+        let requests = llm.requests.lock().unwrap();
+        assert!(
+            requests[0]
+                .instructions
+                .iter()
+                .any(|i| i.contains("use `core` as the scope"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ticket_from_branch() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["feat: add parser"]));
+        let ticket = TicketReference::new(
+            &[r"[A-Z]+-\d+".to_string()],
+            crate::TicketPlacement::Footer,
+            "Refs: {ticket}".to_string(),
+        )
+        .unwrap();
+        let service =
+            CommitServiceImpl::new(Box::new(llm), Box::new(git())).with_ticket(Some(ticket));
+
+        assert_eq!(
+            service.commit(false).await.unwrap(),
+            "feat: add parser\n\nRefs: PROJ-42"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subject_ticket_fits_header_limit() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["feat: add a parser", "feat: parse"]));
+        let ticket = TicketReference::new(
+            &[r"[A-Z]+-\d+".to_string()],
+            crate::TicketPlacement::Subject,
+            "{ticket}".to_string(),
+        )
+        .unwrap();
+        let rules = CommitRules {
+            max_subject_length: 20,
+            ..CommitRules::default()
+        };
+        let service = CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git()))
+            .with_rules(Some(rules))
+            .with_ticket(Some(ticket));
+
+        // "feat: add a parser" fits 20 characters, but not with "PROJ-42 " added
+        let message = service.commit(false).await.unwrap();
+        assert_eq!(message, "feat: PROJ-42 parse");
+        assert!(message.len() <= 20);
+        assert_eq!(llm.requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reprompts_with_violations() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&[
+            "Added parser",
+            "feat: added parser",
+            "feat: add parser",
+        ]));
+        let service =
+            CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git())).with_max_retries(3);
+
+        assert_eq!(service.commit(false).await.unwrap(), "feat: add parser");
+
+        let requests = llm.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        let feedback = requests[2].instructions.last().unwrap();
+        assert!(feedback.contains("feat: added parser"));
+        assert!(feedback.contains("imperative mood"));
+    }
+
+    #[tokio::test]
+    async fn test_fails_when_retries_run_out() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["Added parser", "added parser"]));
+        let service = CommitServiceImpl::new(Box::new(llm), Box::new(git())).with_max_retries(1);
+
+        let error = service.commit(false).await.unwrap_err().to_string();
+        assert!(error.contains("after 1 retries"));
+        assert!(error.contains("Last attempt:\nadded parser"));
+    }
+
+    #[tokio::test]
+    async fn test_reprompts_in_language() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["feat: add parser", "feat: 新增解析器"]));
+        let service = CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git()))
+            .with_language(Some("zh-TW".to_string()));
+
+        assert_eq!(service.commit(false).await.unwrap(), "feat: 新增解析器");
+
+        let requests = llm.requests.lock().unwrap();
+        assert!(
+            requests[0]
+                .instructions
+                .iter()
+                .any(|i| i.contains("Traditional Chinese (zh-TW)"))
+        );
+        assert!(
+            requests[1]
+                .instructions
+                .last()
+                .unwrap()
+                .contains("must be written in Traditional Chinese")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_summary_skips_validation() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["Merge branch 'feature/login'"]));
+        let git = FakeGitRepository {
+            merge: Some(MergeState {
+                kind: MergeKind::Merge,
+                incoming: Some("feature/login".to_string()),
+                commits: vec![commit_info("feat: add login")],
+                conflicts: vec!["src/auth.rs".to_string()],
+            }),
+            ..git()
+        };
+        let service = CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git));
+
+        assert_eq!(
+            service.commit(false).await.unwrap(),
+            "Merge branch 'feature/login'"
+        );
+
+        let requests = llm.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let instruction = &requests[0].instructions[0];
+        assert!(instruction.contains("Merge branch 'feature/login'"));
+        assert!(instruction.contains("- feat: add login"));
+        assert!(instruction.contains("- src/auth.rs"));
+    }
+
+    /// `git()` on a branch that contains everything on `main`
+    fn branch_of_main() -> FakeGitRepository {
+        let mut repo = git();
+        repo.ranges.insert("HEAD..main".to_string(), Vec::new());
+        repo
+    }
+
+    #[tokio::test]
+    async fn test_squash_message() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&["feat: add parser"]));
+        let repo = branch_of_main();
+        let service = CommitServiceImpl::new(Box::new(llm.clone()), Box::new(repo.clone()));
+
+        assert_eq!(
+            service.squash_message("main..HEAD").await.unwrap(),
+            "feat: add parser"
+        );
+
+        let requests = llm.requests.lock().unwrap();
+        assert!(requests[0].diff.contains("src/parser.rs"));
+        assert!(requests[0].instructions[0].contains("- fixup! feat: add parser"));
+        // Diffed from the merge base, not against main's tip
+        assert_eq!(*repo.diffed_ranges.lock().unwrap(), vec!["main...HEAD"]);
+    }
+
+    #[tokio::test]
+    async fn test_squash_rejects_diverged_base() {
+        let llm = std::sync::Arc::new(ScriptedLlm::new(&[]));
+        let service = CommitServiceImpl::new(Box::new(llm.clone()), Box::new(git()));
+
+        let error = service.squash_message("main..").await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("main is not an ancestor of HEAD")
+        );
+        assert!(llm.requests.lock().unwrap().is_empty());
     }
 }
@@ -0,0 +1,89 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::interfaces::git_repository::{CommitInfo, GitRepository};
+use crate::interfaces::llm_generater::LlmGenerater;
+use crate::prompts::EXPLAIN_PROMPT;
+
+#[async_trait]
+pub trait ExplainService {
+    /// Explain in plain language what the commits in `range` did and why
+    async fn explain(&self, range: &str) -> anyhow::Result<String>;
+}
+
+pub struct ExplainServiceImpl {
+    llm_generater: Box<dyn LlmGenerater>,
+    git_repository: Box<dyn GitRepository>,
+}
+
+impl ExplainServiceImpl {
+    pub fn new(
+        llm_generater: Box<dyn LlmGenerater>,
+        git_repository: Box<dyn GitRepository>,
+    ) -> Self {
+        Self {
+            llm_generater,
+            git_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl ExplainService for ExplainServiceImpl {
+    async fn explain(&self, range: &str) -> anyhow::Result<String> {
+        let commits = self.git_repository.log(range).await?;
+        if commits.is_empty() {
+            return Err(anyhow!("No commits in {}", range));
+        }
+        let patches = self.git_repository.show_range(range).await?;
+
+        // Patches go last so truncation never cuts the commit list
+        let content = format!(
+            "{}\n## Patches\n\n```diff\n{}\n```\n",
+            describe_commits(&commits),
+            patches.trim_end()
+        );
+
+        let response = self.llm_generater.complete(EXPLAIN_PROMPT, content).await?;
+        let explanation = response.trim().to_string();
+        if explanation.is_empty() {
+            return Err(anyhow!("The model did not return an explanation"));
+        }
+        Ok(explanation)
+    }
+}
+
+/// List the commits with short hashes and full messages
+fn describe_commits(commits: &[CommitInfo]) -> String {
+    let mut text = String::from("## Commits\n\n");
+    for commit in commits {
+        let short = &commit.hash[..commit.hash.len().min(7)];
+        text.push_str(&format!(
+            "- {} {} ({})\n",
+            short, commit.subject, commit.date
+        ));
+        for line in commit.body.lines().filter(|l| !l.trim().is_empty()) {
+            text.push_str(&format!("  {}\n", line));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_commits() {
+        let commits = vec![CommitInfo {
+            hash: "0123456789abcdef".to_string(),
+            date: "2019-03-04".to_string(),
+            subject: "fix stuff".to_string(),
+            body: "Handle empty input\n\nSee #12".to_string(),
+        }];
+        assert_eq!(
+            describe_commits(&commits),
+            "## Commits\n\n- 0123456 fix stuff (2019-03-04)\n  Handle empty input\n  See #12\n"
+        );
+    }
+}
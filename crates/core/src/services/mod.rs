@@ -1 +1,8 @@
+pub mod branch_service;
+pub mod bump_service;
+pub mod changelog_service;
 pub mod commit_service;
+pub mod explain_service;
+pub mod pr_service;
+pub mod review_service;
+pub mod split_service;
@@ -0,0 +1,179 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::interfaces::git_repository::GitRepository;
+use crate::interfaces::llm_generater::LlmGenerater;
+use crate::prompts::PR_PROMPT;
+
+/// A generated pull request title and Markdown description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequest {
+    pub title: String,
+    pub body: String,
+}
+
+impl PullRequest {
+    /// Render as a Markdown document with the title as heading
+    pub fn to_markdown(&self) -> String {
+        format!("# {}\n\n{}\n", self.title, self.body)
+    }
+}
+
+#[async_trait]
+pub trait PrService {
+    /// Generate a pull request for the commits between `base` and `head`
+    async fn generate(&self, base: &str, head: &str) -> anyhow::Result<PullRequest>;
+}
+
+pub struct PrServiceImpl {
+    llm_generater: Box<dyn LlmGenerater>,
+    git_repository: Box<dyn GitRepository>,
+    template: Option<String>,
+}
+
+impl PrServiceImpl {
+    pub fn new(
+        llm_generater: Box<dyn LlmGenerater>,
+        git_repository: Box<dyn GitRepository>,
+    ) -> Self {
+        Self {
+            llm_generater,
+            git_repository,
+            template: None,
+        }
+    }
+
+    /// Fill in the repository's pull request template
+    pub fn with_template(mut self, template: Option<String>) -> Self {
+        self.template = template;
+        self
+    }
+}
+
+#[async_trait]
+impl PrService for PrServiceImpl {
+    async fn generate(&self, base: &str, head: &str) -> anyhow::Result<PullRequest> {
+        let commits = self
+            .git_repository
+            .log(&format!("{}..{}", base, head))
+            .await?;
+        if commits.is_empty() {
+            return Err(anyhow!("No commits between {} and {}", base, head));
+        }
+        // Three dots diff against the merge base, like the PR view does
+        let diff = self
+            .git_repository
+            .diff_range(&format!("{}...{}", base, head))
+            .await?;
+
+        // The diff goes last so truncation never cuts the commits or template
+        let mut content = String::from("## Commits\n\n");
+        for commit in &commits {
+            content.push_str(&format!("- {}\n", commit.subject));
+            for line in commit.body.lines().filter(|l| !l.trim().is_empty()) {
+                content.push_str(&format!("  {}\n", line));
+            }
+        }
+        if let Some(template) = &self.template {
+            content.push_str(&format!(
+                "\n## Pull Request Template\n\n{}\n",
+                template.trim_end()
+            ));
+        }
+        content.push_str(&format!("\n## Diff\n\n```diff\n{}\n```\n", diff.trim_end()));
+
+        let response = self.llm_generater.complete(PR_PROMPT, content).await?;
+        parse_pull_request(&response)
+    }
+}
+
+/// Split the response into title and body
+///
+/// The body is Markdown, so it is kept as written: the commit message
+/// sanitizer would unwrap code blocks, collapse blank lines and drop the
+/// trailing spaces of hard line breaks.
+fn parse_pull_request(response: &str) -> anyhow::Result<PullRequest> {
+    let mut lines: Vec<&str> = response.trim().lines().collect();
+
+    // Unwrap a response that is one fenced block; a title never starts with a fence
+    if lines.len() >= 2
+        && lines[0].trim_start().starts_with("```")
+        && lines[lines.len() - 1].trim() == "```"
+    {
+        lines = lines[1..lines.len() - 1].to_vec();
+    }
+
+    let mut lines = lines.into_iter().skip_while(|line| line.trim().is_empty());
+    let title = lines.next().unwrap_or_default();
+    let title = title.trim().trim_start_matches('#').trim();
+    let title = title.strip_prefix("Title:").unwrap_or(title).trim();
+    let title = title.trim_matches(['"', '`']).trim();
+    if title.is_empty() {
+        return Err(anyhow!("The model did not return a pull request title"));
+    }
+
+    let body: Vec<&str> = lines.skip_while(|line| line.trim().is_empty()).collect();
+    Ok(PullRequest {
+        title: title.to_string(),
+        body: body.join("\n").trim_end().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FakeGitRepository, ScriptedLlm, commit_info};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_pull_request() {
+        let pr =
+            parse_pull_request("# Title: feat: add login\n\n## Summary\n\nAdds login.").unwrap();
+        assert_eq!(pr.title, "feat: add login");
+        assert_eq!(pr.body, "## Summary\n\nAdds login.");
+        assert_eq!(
+            pr.to_markdown(),
+            "# feat: add login\n\n## Summary\n\nAdds login.\n"
+        );
+
+        assert!(parse_pull_request("   ").is_err());
+    }
+
+    #[test]
+    fn test_keeps_markdown_body() {
+        let body =
+            "## Usage\n\n```bash\ngit gen pr\n```\n\n\nFirst line  \nsecond line\n> \"quoted\"";
+        let pr = parse_pull_request(&format!("feat: add pr command\n\n{}\n", body)).unwrap();
+        assert_eq!(pr.title, "feat: add pr command");
+        assert_eq!(pr.body, body);
+
+        let pr = parse_pull_request("```markdown\n# Fix login\n\nBody\n```").unwrap();
+        assert_eq!(pr.title, "Fix login");
+        assert_eq!(pr.body, "Body");
+    }
+
+    #[tokio::test]
+    async fn test_includes_template() {
+        let llm = Arc::new(ScriptedLlm::new(&[
+            "feat: add login\n\n## Summary\n\nAdds login.",
+        ]));
+        let git = FakeGitRepository {
+            commits: vec![commit_info("feat: add login")],
+            range_diff: "diff --git a/src/auth.rs b/src/auth.rs".to_string(),
+            ..Default::default()
+        };
+        let service = PrServiceImpl::new(Box::new(llm.clone()), Box::new(git))
+            .with_template(Some("## Summary\n\n## Testing\n".to_string()));
+
+        let pr = service.generate("main", "HEAD").await.unwrap();
+        assert_eq!(pr.title, "feat: add login");
+
+        let prompts = llm.prompts.lock().unwrap();
+        let template = prompts[0].find("## Pull Request Template").unwrap();
+        assert!(
+            prompts[0][template..]
+                .starts_with("## Pull Request Template\n\n## Summary\n\n## Testing\n")
+        );
+        assert!(template < prompts[0].find("## Diff").unwrap());
+    }
+}
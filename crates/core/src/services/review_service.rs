@@ -0,0 +1,193 @@
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::diff::FilePatch;
+use crate::interfaces::git_repository::GitRepository;
+use crate::interfaces::llm_generater::LlmGenerater;
+use crate::prompts::REVIEW_PROMPT;
+
+/// How serious a review finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A single problem found in the staged changes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub file: String,
+    /// Line in the new version of the file
+    #[serde(default)]
+    pub line: Option<u32>,
+    pub severity: Severity,
+    /// `bug`, `debug`, `tests`, `risk` or `style`
+    pub category: String,
+    pub message: String,
+}
+
+/// Findings of a review, sorted by file and line
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    pub findings: Vec<Finding>,
+}
+
+impl Review {
+    /// Number of findings at or above the given severity
+    pub fn count_at_least(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity >= severity)
+            .count()
+    }
+}
+
+#[async_trait]
+pub trait ReviewService {
+    /// Review the staged changes
+    async fn review(&self) -> anyhow::Result<Review>;
+}
+
+pub struct ReviewServiceImpl {
+    llm_generater: Box<dyn LlmGenerater>,
+    git_repository: Box<dyn GitRepository>,
+}
+
+impl ReviewServiceImpl {
+    pub fn new(
+        llm_generater: Box<dyn LlmGenerater>,
+        git_repository: Box<dyn GitRepository>,
+    ) -> Self {
+        Self {
+            llm_generater,
+            git_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl ReviewService for ReviewServiceImpl {
+    async fn review(&self) -> anyhow::Result<Review> {
+        let patches = self.git_repository.get_staged_patches().await?;
+        if patches.is_empty() {
+            return Err(anyhow!("No staged changes found"));
+        }
+
+        let response = self
+            .llm_generater
+            .complete(REVIEW_PROMPT, number_lines(&patches))
+            .await?;
+        parse_review(&response)
+    }
+}
+
+/// Render patches with new-file line numbers so the model can cite lines
+fn number_lines(patches: &[FilePatch]) -> String {
+    let mut text = String::new();
+    for patch in patches {
+        text.push_str(&format!("## {}\n\n", patch.path));
+        if patch.hunks.is_empty() {
+            text.push_str("(no textual changes)\n\n");
+            continue;
+        }
+
+        text.push_str("```diff\n");
+        for hunk in &patch.hunks {
+            text.push_str(&hunk.header);
+            text.push('\n');
+            let mut line_number = new_start(&hunk.header).unwrap_or(1);
+            for line in &hunk.lines {
+                if line.starts_with('-') || line.starts_with('\\') {
+                    text.push_str(&format!("{:>6} {}\n", "", line));
+                } else {
+                    text.push_str(&format!("{:>6} {}\n", line_number, line));
+                    line_number += 1;
+                }
+            }
+        }
+        text.push_str("```\n\n");
+    }
+    text
+}
+
+/// Start line of the new side from `@@ -a,b +c,d @@`
+fn new_start(header: &str) -> Option<u32> {
+    let new = header
+        .split_whitespace()
+        .find(|part| part.starts_with('+'))?;
+    new[1..].split(',').next()?.parse().ok()
+}
+
+/// Parse the model's JSON findings
+fn parse_review(response: &str) -> anyhow::Result<Review> {
+    // Models wrap JSON in fences or prose despite being told not to
+    let json = match (response.find('{'), response.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => response,
+    };
+    let mut review: Review =
+        serde_json::from_str(json).context("The model did not return a valid review")?;
+
+    review
+        .findings
+        .sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    Ok(review)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::Hunk;
+
+    #[test]
+    fn test_number_lines() {
+        let patch = FilePatch {
+            path: "src/lib.rs".to_string(),
+            header: Vec::new(),
+            hunks: vec![Hunk {
+                header: "@@ -10,3 +12,3 @@ fn main() {".to_string(),
+                lines: vec![
+                    " let a = 1;".to_string(),
+                    "-let b = 2;".to_string(),
+                    "+let b = 3;".to_string(),
+                ],
+            }],
+        };
+        assert_eq!(
+            number_lines(&[patch]),
+            "## src/lib.rs\n\n```diff\n@@ -10,3 +12,3 @@ fn main() {\n    12  let a = 1;\n       -let b = 2;\n    13 +let b = 3;\n```\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_review() {
+        let review = parse_review(
+            r#"```json
+{"findings": [
+  {"file": "src/main.rs", "line": 7, "severity": "warning", "category": "debug", "message": "Remove println!"},
+  {"file": "src/lib.rs", "severity": "error", "category": "bug", "message": "Unchecked index"}
+]}
+```"#,
+        )
+        .unwrap();
+
+        assert_eq!(review.findings[0].file, "src/lib.rs");
+        assert_eq!(review.findings[0].line, None);
+        assert_eq!(review.count_at_least(Severity::Warning), 2);
+        assert_eq!(review.count_at_least(Severity::Error), 1);
+        assert!(parse_review("looks good").is_err());
+    }
+}
@@ -0,0 +1,483 @@
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::diff::{FilePatch, render_patches};
+use crate::interfaces::git_repository::{CommitOptions, GitRepository};
+use crate::interfaces::llm_generater::LlmGenerater;
+use crate::message::language::language_instruction;
+use crate::message::sanitizer::sanitize;
+use crate::message::style::MessageStyle;
+use crate::message::validator::CommitRules;
+use crate::prompts::SPLIT_PROMPT;
+
+/// A piece of the staged changes that is staged as a whole
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Index into `SplitPlan::patches`
+    pub file: usize,
+    /// Hunks of that file; all of them for atomic patches, none if the patch has no hunks
+    pub hunks: Vec<usize>,
+}
+
+/// One commit of a split plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCommit {
+    pub message: String,
+    /// Indices into `SplitPlan::chunks`
+    pub chunks: Vec<usize>,
+}
+
+/// How the staged changes are split into commits
+#[derive(Debug, Clone)]
+pub struct SplitPlan {
+    /// The staged changes the plan was made for
+    pub patches: Vec<FilePatch>,
+    pub chunks: Vec<Chunk>,
+    pub commits: Vec<PlannedCommit>,
+    /// Chunks the model left out; they stay staged after the split
+    pub unassigned: Vec<usize>,
+}
+
+impl SplitPlan {
+    /// Build the patches that stage exactly the given chunks
+    pub fn patches_for(&self, chunks: &[usize]) -> Vec<FilePatch> {
+        let mut files: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for chunk in chunks.iter().filter_map(|&i| self.chunks.get(i)) {
+            files
+                .entry(chunk.file)
+                .or_default()
+                .extend(chunk.hunks.iter().copied());
+        }
+
+        files
+            .into_iter()
+            .map(|(file, mut hunks)| {
+                hunks.sort_unstable();
+                self.patches[file].with_hunks(&hunks)
+            })
+            .collect()
+    }
+
+    /// Paths touched by the given chunks, in patch order
+    pub fn files_for(&self, chunks: &[usize]) -> Vec<&str> {
+        let mut files: Vec<usize> = chunks
+            .iter()
+            .filter_map(|&i| self.chunks.get(i))
+            .map(|chunk| chunk.file)
+            .collect();
+        files.sort_unstable();
+        files.dedup();
+        files
+            .into_iter()
+            .map(|file| self.patches[file].path.as_str())
+            .collect()
+    }
+}
+
+#[async_trait]
+pub trait SplitService {
+    /// Ask the model how to split the staged changes into commits
+    async fn plan(&self) -> anyhow::Result<SplitPlan>;
+
+    /// Create the planned commits in order by restaging subsets of the changes
+    async fn apply(&self, plan: &SplitPlan) -> anyhow::Result<()>;
+}
+
+pub struct SplitServiceImpl {
+    llm_generater: Box<dyn LlmGenerater>,
+    git_repository: Box<dyn GitRepository>,
+    rules: Option<CommitRules>,
+    commit_options: CommitOptions,
+    language: Option<String>,
+    style: MessageStyle,
+}
+
+impl SplitServiceImpl {
+    pub fn new(
+        llm_generater: Box<dyn LlmGenerater>,
+        git_repository: Box<dyn GitRepository>,
+    ) -> Self {
+        Self {
+            llm_generater,
+            git_repository,
+            rules: Some(CommitRules::default()),
+            commit_options: CommitOptions::default(),
+            language: None,
+            style: MessageStyle::default(),
+        }
+    }
+
+    /// Describe the given rules to the model, or skip them with `None`
+    pub fn with_rules(mut self, rules: Option<CommitRules>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Describe and format commit messages in the given style
+    pub fn with_style(mut self, style: MessageStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Write commit messages in the given language, e.g. `zh-TW`
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Set the options passed to `git commit` for every commit
+    pub fn with_commit_options(mut self, commit_options: CommitOptions) -> Self {
+        self.commit_options = commit_options;
+        self
+    }
+
+    /// Build the system prompt with the commit rules appended
+    fn system_prompt(&self) -> String {
+        let mut prompt = SPLIT_PROMPT.to_string();
+        let mut instructions = self.style.instructions.clone();
+        instructions.extend(
+            self.rules
+                .as_ref()
+                .map(|rules| rules.prompt_instructions())
+                .unwrap_or_default(),
+        );
+        instructions.extend(self.language.as_deref().map(language_instruction));
+        if !instructions.is_empty() {
+            prompt.push_str("\n## Commit Message Rules\n\n");
+            for instruction in instructions {
+                prompt.push_str(&format!("- {}\n", instruction));
+            }
+        }
+        prompt
+    }
+
+    /// Stage everything that is not committed yet after a failure at commit `from`
+    async fn restore(&self, plan: &SplitPlan, from: usize) -> anyhow::Result<()> {
+        let mut remaining: Vec<usize> = plan.commits[from..]
+            .iter()
+            .flat_map(|commit| commit.chunks.iter().copied())
+            .collect();
+        remaining.extend(plan.unassigned.iter().copied());
+
+        self.git_repository.reset_index().await?;
+        self.git_repository
+            .stage_patches(&plan.patches_for(&remaining))
+            .await
+    }
+}
+
+#[async_trait]
+impl SplitService for SplitServiceImpl {
+    async fn plan(&self) -> anyhow::Result<SplitPlan> {
+        let patches = self.git_repository.get_staged_patches().await?;
+        if patches.is_empty() {
+            return Err(anyhow!("No staged changes found"));
+        }
+
+        let chunks = chunks_of(&patches);
+        let response = self
+            .llm_generater
+            .complete(&self.system_prompt(), describe_chunks(&patches, &chunks))
+            .await?;
+        let mut commits = parse_plan(&response, chunks.len())?;
+        for commit in &mut commits {
+            commit.message = self.style.format(&commit.message);
+        }
+
+        let unassigned = (0..chunks.len())
+            .filter(|i| !commits.iter().any(|c| c.chunks.contains(i)))
+            .collect();
+
+        Ok(SplitPlan {
+            patches,
+            chunks,
+            commits,
+            unassigned,
+        })
+    }
+
+    async fn apply(&self, plan: &SplitPlan) -> anyhow::Result<()> {
+        // Restaging subsets of a different index would silently drop changes
+        let staged = self.git_repository.get_staged_patches().await?;
+        if render_patches(&staged) != render_patches(&plan.patches) {
+            return Err(anyhow!(
+                "The staged changes were modified after planning; run split again"
+            ));
+        }
+
+        self.git_repository.reset_index().await?;
+
+        for (index, commit) in plan.commits.iter().enumerate() {
+            let result = async {
+                self.git_repository
+                    .stage_patches(&plan.patches_for(&commit.chunks))
+                    .await?;
+                self.git_repository
+                    .commit(&commit.message, &self.commit_options)
+                    .await
+            }
+            .await;
+
+            if let Err(e) = result {
+                self.restore(plan, index).await.with_context(|| {
+                    format!(
+                        "Failed to restage changes after commit {} failed",
+                        index + 1
+                    )
+                })?;
+                return Err(e.context(format!(
+                    "Failed to create commit {} of {}; its changes and all later ones are staged again",
+                    index + 1,
+                    plan.commits.len()
+                )));
+            }
+        }
+
+        self.git_repository
+            .stage_patches(&plan.patches_for(&plan.unassigned))
+            .await
+    }
+}
+
+/// Cut patches into chunks: one per hunk, or one per file for atomic patches
+fn chunks_of(patches: &[FilePatch]) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    for (file, patch) in patches.iter().enumerate() {
+        if patch.is_atomic() {
+            chunks.push(Chunk {
+                file,
+                hunks: (0..patch.hunks.len()).collect(),
+            });
+        } else {
+            chunks.extend((0..patch.hunks.len()).map(|hunk| Chunk {
+                file,
+                hunks: vec![hunk],
+            }));
+        }
+    }
+    chunks
+}
+
+/// Render the numbered chunks for the model, leaving out binary data
+fn describe_chunks(patches: &[FilePatch], chunks: &[Chunk]) -> String {
+    let mut text = String::new();
+    for (number, chunk) in chunks.iter().enumerate() {
+        let patch = &patches[chunk.file];
+        let mut body = if patch.is_atomic() {
+            patch
+                .header
+                .iter()
+                .take_while(|line| !line.starts_with("GIT binary patch"))
+                .map(|line| format!("{}\n", line))
+                .collect::<String>()
+        } else {
+            String::new()
+        };
+        for hunk in chunk.hunks.iter().map(|&i| &patch.hunks[i]) {
+            body.push_str(&hunk.header);
+            body.push('\n');
+            for line in &hunk.lines {
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+
+        text.push_str(&format!(
+            "## Chunk {}: {}\n\n```diff\n{}```\n\n",
+            number + 1,
+            patch.path,
+            body
+        ));
+    }
+    text
+}
+
+#[derive(Deserialize)]
+struct RawPlan {
+    commits: Vec<RawCommit>,
+}
+
+#[derive(Deserialize)]
+struct RawCommit {
+    message: String,
+    chunks: Vec<usize>,
+}
+
+/// Parse and check the model's JSON plan; chunk numbers are 1-based in the prompt
+fn parse_plan(response: &str, chunk_count: usize) -> anyhow::Result<Vec<PlannedCommit>> {
+    // Models wrap JSON in fences or prose despite being told not to
+    let json = match (response.find('{'), response.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => response,
+    };
+    let raw: RawPlan =
+        serde_json::from_str(json).context("The model did not return a valid split plan")?;
+
+    let mut seen = vec![false; chunk_count];
+    let mut commits = Vec::new();
+    for commit in raw.commits {
+        let mut chunks = Vec::new();
+        for number in commit.chunks {
+            let index = number.wrapping_sub(1);
+            match seen.get_mut(index) {
+                Some(true) => {
+                    return Err(anyhow!("The split plan uses chunk {} twice", number));
+                }
+                Some(used) => *used = true,
+                None => return Err(anyhow!("The split plan refers to unknown chunk {}", number)),
+            }
+            chunks.push(index);
+        }
+        if chunks.is_empty() {
+            continue;
+        }
+
+        let message = sanitize(&commit.message);
+        if message.is_empty() {
+            return Err(anyhow!(
+                "The split plan contains a commit without a message"
+            ));
+        }
+        commits.push(PlannedCommit { message, chunks });
+    }
+
+    if commits.is_empty() {
+        return Err(anyhow!("The split plan contains no commits"));
+    }
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interfaces::llm_generater::{GenerationRequest, RequestPreview};
+    use crate::test_support::{FakeGitRepository, hunk};
+
+    fn patches() -> Vec<FilePatch> {
+        vec![
+            FilePatch {
+                path: "src/lib.rs".to_string(),
+                header: vec!["diff --git a/src/lib.rs b/src/lib.rs".to_string()],
+                hunks: vec![hunk("@@ -1 +1 @@", "+fix"), hunk("@@ -9 +9 @@", "+feat")],
+            },
+            FilePatch {
+                path: "README.md".to_string(),
+                header: vec![
+                    "diff --git a/README.md b/README.md".to_string(),
+                    "new file mode 100644".to_string(),
+                ],
+                hunks: vec![hunk("@@ -0,0 +1 @@", "+docs")],
+            },
+        ]
+    }
+
+    struct PlanLlm(String);
+
+    #[async_trait]
+    impl LlmGenerater for PlanLlm {
+        async fn generate_commit_message(
+            &self,
+            _request: GenerationRequest,
+        ) -> anyhow::Result<String> {
+            unreachable!()
+        }
+
+        async fn complete(
+            &self,
+            system_prompt: &str,
+            user_message: String,
+        ) -> anyhow::Result<String> {
+            assert!(system_prompt.contains("Commit Message Rules"));
+            assert!(user_message.contains("## Chunk 3: README.md"));
+            Ok(self.0.clone())
+        }
+
+        fn preview_commit_message(&self, _request: GenerationRequest) -> RequestPreview {
+            unreachable!()
+        }
+
+        fn preview(&self, _system_prompt: &str, _user_message: String) -> RequestPreview {
+            unreachable!()
+        }
+
+        fn usage(&self) -> crate::TokenUsage {
+            crate::TokenUsage::default()
+        }
+    }
+
+    fn service(repo: &FakeGitRepository, response: &str) -> SplitServiceImpl {
+        SplitServiceImpl::new(
+            Box::new(PlanLlm(response.to_string())),
+            Box::new(repo.clone()),
+        )
+    }
+
+    #[test]
+    fn test_parse_plan() {
+        let commits = parse_plan(
+            "```json\n{\"commits\": [{\"message\": \"fix: a\", \"chunks\": [2]}, {\"message\": \"feat: b\", \"chunks\": []}]}\n```",
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            commits,
+            vec![PlannedCommit {
+                message: "fix: a".to_string(),
+                chunks: vec![1]
+            }]
+        );
+
+        let duplicate =
+            r#"{"commits": [{"message": "a", "chunks": [1]}, {"message": "b", "chunks": [1]}]}"#;
+        assert!(parse_plan(duplicate, 3).is_err());
+        let unknown = r#"{"commits": [{"message": "a", "chunks": [0]}]}"#;
+        assert!(parse_plan(unknown, 3).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_split_commits_in_order() {
+        let repo = FakeGitRepository::staging(patches());
+        let service = service(
+            &repo,
+            r#"{"commits": [{"message": "fix: a", "chunks": [1]}, {"message": "docs: c", "chunks": [3]}]}"#,
+        );
+
+        let plan = service.plan().await.unwrap();
+        assert_eq!(plan.chunks.len(), 3);
+        assert_eq!(plan.unassigned, vec![1]);
+        assert_eq!(plan.files_for(&[0, 1]), vec!["src/lib.rs"]);
+
+        service.apply(&plan).await.unwrap();
+        assert_eq!(
+            repo.log(),
+            vec![
+                "reset",
+                "stage src/lib.rs +fix",
+                "commit fix: a",
+                "stage README.md +docs",
+                "commit docs: c",
+                "stage src/lib.rs +feat",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restages_remaining_changes_on_failure() {
+        let repo = FakeGitRepository {
+            fail_commit: true,
+            ..FakeGitRepository::staging(patches())
+        };
+        let service = service(
+            &repo,
+            r#"{"commits": [{"message": "fix: a", "chunks": [1, 2]}, {"message": "docs: c", "chunks": [3]}]}"#,
+        );
+
+        let plan = service.plan().await.unwrap();
+        let error = service.apply(&plan).await.unwrap_err();
+        assert!(error.to_string().contains("commit 1 of 2"));
+        assert_eq!(repo.log().last().unwrap(), "stage README.md +docs");
+        assert_eq!(repo.staged.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,265 @@
+//! Test doubles shared by the service tests
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::diff::{ChangeSet, FilePatch, Hunk, render_patches};
+use crate::interfaces::git_repository::{CommitInfo, CommitOptions, GitRepository, MergeState};
+use crate::interfaces::llm_generater::{
+    GenerationRequest, LlmGenerater, RequestPreview, TokenUsage,
+};
+use crate::message::parser::Footer;
+
+/// In-memory repository that records index operations and commits
+///
+/// Clones share the staged patches, the operation log and the saved message,
+/// so a test can keep a clone to inspect what a service did with its own.
+#[derive(Clone, Default)]
+pub(crate) struct FakeGitRepository {
+    pub staged: Arc<Mutex<Vec<FilePatch>>>,
+    /// `reset`, `stage <path> <first line of each hunk>` and `commit <message>`
+    pub log: Arc<Mutex<Vec<String>>>,
+    /// Options of every commit, in order
+    pub commit_options: Arc<Mutex<Vec<CommitOptions>>>,
+    pub saved: Arc<Mutex<Option<String>>>,
+    pub merge: Option<MergeState>,
+    /// Returned by `log` for any range not in `ranges`
+    pub commits: Vec<CommitInfo>,
+    /// Returned by `log` for specific ranges
+    pub ranges: HashMap<String, Vec<CommitInfo>>,
+    /// Returned by `diff_range` for any range
+    pub range_diff: String,
+    /// Ranges passed to `diff_range`, in order
+    pub diffed_ranges: Arc<Mutex<Vec<String>>>,
+    pub branch: Option<String>,
+    pub fail_commit: bool,
+}
+
+impl FakeGitRepository {
+    /// A repository with `patches` staged
+    pub fn staging(patches: Vec<FilePatch>) -> Self {
+        let repo = Self::default();
+        *repo.staged.lock().unwrap() = patches;
+        repo
+    }
+
+    pub fn log(&self) -> Vec<String> {
+        self.log.lock().unwrap().clone()
+    }
+}
+
+/// A one-line hunk
+pub(crate) fn hunk(header: &str, line: &str) -> Hunk {
+    Hunk {
+        header: header.to_string(),
+        lines: vec![line.to_string()],
+    }
+}
+
+/// A modified file with the given hunks
+pub(crate) fn file_patch(path: &str, hunks: Vec<Hunk>) -> FilePatch {
+    FilePatch {
+        path: path.to_string(),
+        header: vec![format!("diff --git a/{} b/{}", path, path)],
+        hunks,
+    }
+}
+
+pub(crate) fn commit_info(subject: &str) -> CommitInfo {
+    CommitInfo {
+        hash: "abc123".to_string(),
+        date: "2024-05-01".to_string(),
+        subject: subject.to_string(),
+        body: String::new(),
+    }
+}
+
+#[async_trait]
+impl GitRepository for FakeGitRepository {
+    async fn get_staged_diff(&self) -> anyhow::Result<String> {
+        Ok(render_patches(&self.staged.lock().unwrap()))
+    }
+
+    async fn get_staged_files(&self) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .staged
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| p.path.clone())
+            .collect())
+    }
+
+    async fn get_staged_patches(&self) -> anyhow::Result<Vec<FilePatch>> {
+        Ok(self.staged.lock().unwrap().clone())
+    }
+
+    async fn get_staged_changes(&self) -> anyhow::Result<ChangeSet> {
+        Ok(ChangeSet::default())
+    }
+
+    async fn get_staged_function_patches(&self) -> anyhow::Result<Vec<FilePatch>> {
+        self.get_staged_patches().await
+    }
+
+    async fn get_staged_file(&self, _path: &str) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn reset_index(&self) -> anyhow::Result<()> {
+        self.staged.lock().unwrap().clear();
+        self.log.lock().unwrap().push("reset".to_string());
+        Ok(())
+    }
+
+    async fn stage_patches(&self, patches: &[FilePatch]) -> anyhow::Result<()> {
+        for patch in patches {
+            let hunks: Vec<&str> = patch.hunks.iter().map(|h| h.lines[0].as_str()).collect();
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("stage {} {}", patch.path, hunks.join(",")));
+        }
+        self.staged.lock().unwrap().extend(patches.iter().cloned());
+        Ok(())
+    }
+
+    async fn root_dir(&self) -> anyhow::Result<PathBuf> {
+        Ok(PathBuf::from("."))
+    }
+
+    async fn merge_state(&self) -> anyhow::Result<Option<MergeState>> {
+        Ok(self.merge.clone())
+    }
+
+    async fn log(&self, range: &str) -> anyhow::Result<Vec<CommitInfo>> {
+        Ok(self
+            .ranges
+            .get(range)
+            .cloned()
+            .unwrap_or_else(|| self.commits.clone()))
+    }
+
+    async fn diff_range(&self, range: &str) -> anyhow::Result<String> {
+        self.diffed_ranges.lock().unwrap().push(range.to_string());
+        Ok(self.range_diff.clone())
+    }
+
+    async fn show_range(&self, _range: &str) -> anyhow::Result<String> {
+        Ok(String::new())
+    }
+
+    async fn soft_reset(&self, _rev: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn latest_tag(&self) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn create_tag(&self, _name: &str, _message: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn create_branch(&self, _name: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn current_branch(&self) -> anyhow::Result<Option<String>> {
+        Ok(self.branch.clone())
+    }
+
+    async fn committer_identity(&self) -> anyhow::Result<String> {
+        Ok("Dev <dev@example.com>".to_string())
+    }
+
+    async fn recent_authors(&self, _limit: usize) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn add_trailers(&self, message: &str, trailers: &[Footer]) -> anyhow::Result<String> {
+        if trailers.is_empty() {
+            return Ok(message.to_string());
+        }
+        let lines: Vec<String> = trailers
+            .iter()
+            .map(|t| format!("{}: {}", t.token, t.value))
+            .collect();
+        Ok(format!("{}\n\n{}", message, lines.join("\n")))
+    }
+
+    async fn commit(&self, message: &str, options: &CommitOptions) -> anyhow::Result<()> {
+        if self.fail_commit {
+            return Err(anyhow!("hook declined"));
+        }
+        self.staged.lock().unwrap().clear();
+        self.log.lock().unwrap().push(format!("commit {}", message));
+        self.commit_options.lock().unwrap().push(options.clone());
+        Ok(())
+    }
+
+    async fn save_message(&self, message: &str) -> anyhow::Result<()> {
+        *self.saved.lock().unwrap() = Some(message.to_string());
+        Ok(())
+    }
+
+    async fn saved_message(&self) -> anyhow::Result<Option<String>> {
+        Ok(self.saved.lock().unwrap().clone())
+    }
+
+    async fn clear_saved_message(&self) -> anyhow::Result<()> {
+        *self.saved.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+/// Returns canned responses in order and records every request
+pub(crate) struct ScriptedLlm {
+    responses: Mutex<Vec<String>>,
+    /// Requests passed to `generate_commit_message`
+    pub requests: Mutex<Vec<GenerationRequest>>,
+    /// Requests passed to `preview_commit_message`
+    pub previews: Mutex<Vec<GenerationRequest>>,
+    /// User messages passed to `complete`
+    pub prompts: Mutex<Vec<String>>,
+}
+
+impl ScriptedLlm {
+    pub fn new(responses: &[&str]) -> Self {
+        Self {
+            responses: Mutex::new(responses.iter().rev().map(|r| r.to_string()).collect()),
+            requests: Mutex::new(Vec::new()),
+            previews: Mutex::new(Vec::new()),
+            prompts: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmGenerater for ScriptedLlm {
+    async fn generate_commit_message(&self, request: GenerationRequest) -> anyhow::Result<String> {
+        self.requests.lock().unwrap().push(request);
+        Ok(self.responses.lock().unwrap().pop().unwrap())
+    }
+
+    async fn complete(&self, _system_prompt: &str, user_message: String) -> anyhow::Result<String> {
+        self.prompts.lock().unwrap().push(user_message);
+        Ok(self.responses.lock().unwrap().pop().unwrap())
+    }
+
+    fn preview_commit_message(&self, request: GenerationRequest) -> RequestPreview {
+        self.previews.lock().unwrap().push(request);
+        RequestPreview::default()
+    }
+
+    fn preview(&self, _system_prompt: &str, _user_message: String) -> RequestPreview {
+        RequestPreview::default()
+    }
+
+    fn usage(&self) -> TokenUsage {
+        TokenUsage::default()
+    }
+}
@@ -0,0 +1,383 @@
+use std::cmp::Ordering;
+
+use crate::interfaces::git_repository::CommitInfo;
+use crate::message::parser::parse;
+
+/// Semantic version increment, ordered from smallest to largest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Bump {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        }
+    }
+}
+
+/// A `major.minor.patch` version parsed from a tag like `v1.2.3`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    /// Text in front of the numbers, usually `v` or empty
+    pub prefix: String,
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers, e.g. `rc.1` in `v1.2.3-rc.1`
+    pub pre: Option<String>,
+}
+
+impl Version {
+    /// Parse a tag, ignoring build metadata
+    pub fn parse(tag: &str) -> Option<Self> {
+        let start = tag.find(|c: char| c.is_ascii_digit())?;
+        let (prefix, rest) = tag.split_at(start);
+        let rest = rest.split('+').next()?;
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string()).filter(|p| !p.is_empty())),
+            None => (rest, None),
+        };
+        let mut numbers = core.split('.').map(|n| n.parse::<u64>().ok());
+
+        Some(Self {
+            prefix: prefix.to_string(),
+            major: numbers.next()??,
+            minor: numbers.next()??,
+            patch: numbers.next().unwrap_or(Some(0))?,
+            pre,
+        })
+    }
+
+    /// The next version after applying `bump`
+    ///
+    /// Below 1.0.0 breaking changes only bump the minor version, as Cargo and
+    /// npm treat `0.x` minors as incompatible. A pre-release is released as
+    /// its own version when that is already a big enough step, so a patch on
+    /// `1.2.3-rc.1` gives `1.2.3` and a minor on `1.3.0-rc.1` gives `1.3.0`.
+    pub fn bump(&self, bump: Bump) -> Self {
+        let bump = if self.major == 0 && bump == Bump::Major {
+            Bump::Minor
+        } else {
+            bump
+        };
+
+        let releases_pre = self.pre.is_some()
+            && match bump {
+                Bump::Major => self.minor == 0 && self.patch == 0,
+                Bump::Minor => self.patch == 0,
+                Bump::Patch => true,
+            };
+        let (major, minor, patch) = match bump {
+            _ if releases_pre => (self.major, self.minor, self.patch),
+            Bump::Major => (self.major + 1, 0, 0),
+            Bump::Minor => (self.major, self.minor + 1, 0),
+            Bump::Patch => (self.major, self.minor, self.patch + 1),
+        };
+        Self {
+            prefix: self.prefix.clone(),
+            major,
+            minor,
+            patch,
+            pre: None,
+        }
+    }
+
+    /// Compare by semantic version precedence, ignoring the prefix
+    ///
+    /// A pre-release sorts before its release; pre-release identifiers compare
+    /// numerically when both are numbers and as text otherwise.
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => cmp_pre_release(a, b),
+            })
+    }
+}
+
+/// Compare dot-separated pre-release identifiers
+fn cmp_pre_release(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    // Numeric identifiers have lower precedence than text
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}.{}.{}",
+            self.prefix, self.major, self.minor, self.patch
+        )?;
+        match &self.pre {
+            Some(pre) => write!(f, "-{}", pre),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A reason for a bump, e.g. a breaking change footer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BumpReason {
+    pub bump: Bump,
+    pub description: String,
+}
+
+/// Reasons derived from Conventional Commit types and breaking markers
+///
+/// Commits that are not Conventional Commits count as patches.
+pub fn commit_reasons(commits: &[CommitInfo]) -> Vec<BumpReason> {
+    commits
+        .iter()
+        .filter_map(|commit| {
+            let short = &commit.hash[..commit.hash.len().min(7)];
+            let message = format!("{}\n\n{}", commit.subject, commit.body);
+            let (bump, why) = match parse(&message) {
+                Ok(parsed) if parsed.breaking => (Bump::Major, "breaking change"),
+                Ok(parsed) if parsed.commit_type == "feat" => (Bump::Minor, "new feature"),
+                Ok(parsed) if matches!(parsed.commit_type.as_str(), "fix" | "perf") => {
+                    (Bump::Patch, "fix")
+                }
+                Ok(_) => return None,
+                Err(_) => (Bump::Patch, "unclassified change"),
+            };
+            Some(BumpReason {
+                bump,
+                description: format!("{} {} ({})", short, commit.subject, why),
+            })
+        })
+        .collect()
+}
+
+/// Item keywords that make up a public API surface
+const PUBLIC_ITEMS: &[&str] = &[
+    "pub fn ",
+    "pub async fn ",
+    "pub struct ",
+    "pub enum ",
+    "pub trait ",
+    "pub type ",
+    "pub const ",
+    "pub static ",
+    "pub mod ",
+    "export function ",
+    "export async function ",
+    "export class ",
+    "export interface ",
+    "export type ",
+    "export const ",
+];
+
+/// Reasons derived from removed or changed public items in a diff
+///
+/// Looks at Rust `pub` items and TypeScript/JavaScript exports. An item whose
+/// name disappears is removed; one that reappears with a different line has a
+/// changed signature.
+pub fn api_reasons(diff: &str) -> Vec<BumpReason> {
+    let mut file = String::new();
+    let mut removed: Vec<(String, String, String)> = Vec::new();
+    let mut added: Vec<(String, String)> = Vec::new();
+
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ b/") {
+            file = path.to_string();
+            continue;
+        }
+        if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        }
+        let (sign, code) = match line.split_at_checked(1) {
+            Some((sign @ ("+" | "-"), code)) => (sign, code.trim()),
+            _ => continue,
+        };
+        let Some(name) = public_item(code) else {
+            continue;
+        };
+        let signature = code.split_whitespace().collect::<Vec<_>>().join(" ");
+        if sign == "-" {
+            removed.push((file.clone(), name, signature));
+        } else {
+            added.push((name, signature));
+        }
+    }
+
+    removed
+        .into_iter()
+        .filter_map(|(file, name, signature)| {
+            let matches: Vec<&String> = added
+                .iter()
+                .filter(|(n, _)| *n == name)
+                .map(|(_, s)| s)
+                .collect();
+            let what = if matches.is_empty() {
+                "removed"
+            } else if matches.contains(&&signature) {
+                return None;
+            } else {
+                "changed signature of"
+            };
+            Some(BumpReason {
+                bump: Bump::Major,
+                description: format!("{} public `{}` in {}", what, name, file),
+            })
+        })
+        .collect()
+}
+
+/// `kind name` of a public item declared on this line
+fn public_item(code: &str) -> Option<String> {
+    let keyword = PUBLIC_ITEMS.iter().find(|k| code.starts_with(*k))?;
+    let name: String = code[keyword.len()..]
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+        .collect();
+    if name.is_empty() {
+        return None;
+    }
+    let kind = keyword.trim().rsplit(' ').next().unwrap_or_default();
+    Some(format!("{} {}", kind, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(subject: &str, body: &str) -> CommitInfo {
+        CommitInfo {
+            hash: "abcdef1234".to_string(),
+            date: "2024-05-01".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_version() {
+        let version = Version::parse("v1.2.3+build.5").unwrap();
+        assert_eq!(version.to_string(), "v1.2.3");
+        assert_eq!(version.bump(Bump::Major).to_string(), "v2.0.0");
+        assert_eq!(version.bump(Bump::Minor).to_string(), "v1.3.0");
+        assert_eq!(version.bump(Bump::Patch).to_string(), "v1.2.4");
+        assert_eq!(
+            Version::parse("0.4.1")
+                .unwrap()
+                .bump(Bump::Major)
+                .to_string(),
+            "0.5.0"
+        );
+        assert!(Version::parse("release").is_none());
+    }
+
+    #[test]
+    fn test_release_pre_release() {
+        let version = Version::parse("v1.2.3-rc.1").unwrap();
+        assert_eq!(version.to_string(), "v1.2.3-rc.1");
+        assert_eq!(version.bump(Bump::Patch).to_string(), "v1.2.3");
+        assert_eq!(version.bump(Bump::Minor).to_string(), "v1.3.0");
+        assert_eq!(version.bump(Bump::Major).to_string(), "v2.0.0");
+
+        let version = Version::parse("2.0.0-beta").unwrap();
+        assert_eq!(version.bump(Bump::Minor).to_string(), "2.0.0");
+        assert_eq!(version.bump(Bump::Major).to_string(), "2.0.0");
+    }
+
+    #[test]
+    fn test_precedence() {
+        let mut tags = vec![
+            "v1.0.0",
+            "v1.0.0-rc.1",
+            "v1.0.0-alpha",
+            "v1.0.0-rc.10",
+            "v1.0.0-rc.2",
+            "v0.9.12",
+            "v0.9.9",
+        ];
+        tags.sort_by(|a, b| {
+            Version::parse(a)
+                .unwrap()
+                .cmp_precedence(&Version::parse(b).unwrap())
+        });
+        assert_eq!(
+            tags,
+            vec![
+                "v0.9.9",
+                "v0.9.12",
+                "v1.0.0-alpha",
+                "v1.0.0-rc.1",
+                "v1.0.0-rc.2",
+                "v1.0.0-rc.10",
+                "v1.0.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commit_reasons() {
+        let reasons = commit_reasons(&[
+            commit("feat: add login", ""),
+            commit("fix: typo", "BREAKING CHANGE: renamed flag"),
+            commit("chore: bump deps", ""),
+        ]);
+        assert_eq!(reasons.len(), 2);
+        assert_eq!(reasons[0].bump, Bump::Minor);
+        assert_eq!(reasons[1].bump, Bump::Major);
+        assert_eq!(
+            reasons[1].description,
+            "abcdef1 fix: typo (breaking change)"
+        );
+    }
+
+    #[test]
+    fn test_api_reasons() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,3 @@
+-pub fn parse(input: &str) -> Ast {
++pub fn parse(input: &str, strict: bool) -> Ast {
+-pub struct Legacy;
+-pub fn   render(ast: &Ast) {
++pub fn render(ast: &Ast) {
+ pub(crate) fn helper() {}
+";
+        let reasons: Vec<String> = api_reasons(diff)
+            .into_iter()
+            .map(|r| r.description)
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                "changed signature of public `fn parse` in src/lib.rs",
+                "removed public `struct Legacy` in src/lib.rs",
+            ]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use git_gen_core::CommitInfo;
+
+/// `git log` format separating fields with US and records with RS
+pub const LOG_FORMAT: &str = "--format=%H%x1f%as%x1f%s%x1f%b%x1e";
+
+/// Parse `git log` output produced with [`LOG_FORMAT`]
+pub fn parse_log(output: &str) -> Vec<CommitInfo> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(4, '\x1f');
+            let hash = fields.next().filter(|h| !h.is_empty())?;
+            Some(CommitInfo {
+                hash: hash.to_string(),
+                date: fields.next().unwrap_or_default().to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+                body: fields.next().unwrap_or_default().trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Name of the merged ref from the first line of `MERGE_MSG`
+///
+/// Handles `Merge branch 'x'`, `Merge remote-tracking branch 'origin/x'`,
+/// `Merge tag 'v1'` and `Merge commit 'abc'`, with optional ` into y`.
+pub fn incoming_from_merge_msg(message: &str) -> Option<String> {
+    let subject = message.lines().next()?;
+    let start = subject.find('\'')? + 1;
+    let end = start + subject[start..].find('\'')?;
+    Some(subject[start..end].to_string())
+}
+
+/// Files listed in the commented `# Conflicts:` section of `MERGE_MSG`
+pub fn conflicts_from_merge_msg(message: &str) -> Vec<String> {
+    message
+        .lines()
+        .skip_while(|line| line.trim() != "# Conflicts:")
+        .skip(1)
+        .take_while(|line| line.starts_with('#'))
+        .filter_map(|line| line.strip_prefix("#\t"))
+        .map(|path| path.trim().to_string())
+        .collect()
+}
+
+/// Hashes of the commits listed in `SQUASH_MSG`, newest first as git writes them
+pub fn squashed_hashes(message: &str) -> Vec<String> {
+    message
+        .lines()
+        .filter_map(|line| line.strip_prefix("commit "))
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = "aaa\x1f2024-05-01\x1ffeat: add login\x1fLonger body\n\x1e\nbbb\x1f2024-05-02\x1ffix: typo\x1f\x1e\n";
+        let commits = parse_log(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].date, "2024-05-01");
+        assert_eq!(commits[0].subject, "feat: add login");
+        assert_eq!(commits[0].body, "Longer body");
+        assert_eq!(commits[1].hash, "bbb");
+        assert_eq!(commits[1].body, "");
+    }
+
+    #[test]
+    fn test_merge_msg() {
+        let message = "Merge branch 'feature/login' into main\n\n# Conflicts:\n#\tsrc/auth.rs\n#\tREADME.md\n#\n# It looks like you may be committing a merge.\n";
+        assert_eq!(
+            incoming_from_merge_msg(message).as_deref(),
+            Some("feature/login")
+        );
+        assert_eq!(
+            conflicts_from_merge_msg(message),
+            vec!["src/auth.rs", "README.md"]
+        );
+        assert!(conflicts_from_merge_msg("Merge branch 'x'\n").is_empty());
+    }
+
+    #[test]
+    fn test_squashed_hashes() {
+        let message = "Squashed commit of the following:\n\ncommit 5e1f2a\nAuthor: Dev <dev@example.com>\n\n    feat: add login\n\n    commit message body\n\ncommit 0b3c4d\n";
+        assert_eq!(squashed_hashes(message), vec!["5e1f2a", "0b3c4d"]);
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::anyhow;
+use std::env;
+use std::path::Path;
+
+/// Which identity of a commit is being resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Author,
+    Committer,
+}
+
+impl Role {
+    fn env_prefix(self) -> &'static str {
+        match self {
+            Self::Author => "GIT_AUTHOR",
+            Self::Committer => "GIT_COMMITTER",
+        }
+    }
+
+    fn config_section(self) -> &'static str {
+        match self {
+            Self::Author => "author",
+            Self::Committer => "committer",
+        }
+    }
+}
+
+/// A resolved name and email pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+impl std::fmt::Display for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// Resolve an identity the way git does, reading config in `repo_path`
+///
+/// Config lookups go through `git config`, so `include` and `includeIf`
+/// sections are honored.
+pub fn resolve(repo_path: &Path, role: Role) -> anyhow::Result<Identity> {
+    resolve_with(
+        role,
+        |key| env::var(key).ok(),
+        |key| config_get(repo_path, key),
+    )
+}
+
+/// Resolve an identity from the given environment and config lookups
+///
+/// Precedence mirrors git: `GIT_<ROLE>_NAME`/`GIT_<ROLE>_EMAIL`, then
+/// `<role>.name`/`<role>.email`, then `user.name`/`user.email`, and finally
+/// `EMAIL` for the address.
+pub fn resolve_with(
+    role: Role,
+    env: impl Fn(&str) -> Option<String>,
+    config: impl Fn(&str) -> Option<String>,
+) -> anyhow::Result<Identity> {
+    let prefix = role.env_prefix();
+    let section = role.config_section();
+    let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+
+    let name = non_empty(env(&format!("{}_NAME", prefix)))
+        .or_else(|| non_empty(config(&format!("{}.name", section))))
+        .or_else(|| non_empty(config("user.name")));
+    let email = non_empty(env(&format!("{}_EMAIL", prefix)))
+        .or_else(|| non_empty(config(&format!("{}.email", section))))
+        .or_else(|| non_empty(config("user.email")))
+        .or_else(|| non_empty(env("EMAIL")));
+
+    match (name, email) {
+        (Some(name), Some(email)) => Ok(Identity {
+            name: name.trim().to_string(),
+            email: email.trim().to_string(),
+        }),
+        (name, _) => {
+            let (missing, example) = if name.is_none() {
+                ("name", "\"Your Name\"")
+            } else {
+                ("email", "\"your.email@example.com\"")
+            };
+            Err(anyhow!(
+                "Git {} {} is not configured. Set {}_{}, {}.{} or user.{} (e.g. git config user.{} {}){}",
+                section,
+                missing,
+                prefix,
+                missing.to_uppercase(),
+                section,
+                missing,
+                missing,
+                missing,
+                example,
+                if role == Role::Author {
+                    ", or pass --author"
+                } else {
+                    ""
+                }
+            ))
+        }
+    }
+}
+
+/// Read a config value, returning `None` if it is unset
+fn config_get(repo_path: &Path, key: &str) -> Option<String> {
+    let output = crate::git_command()
+        .args(["config", "--get", key])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(values: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let values: HashMap<String, String> = values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| values.get(key).cloned()
+    }
+
+    #[test]
+    fn test_env_overrides_config() {
+        let identity = resolve_with(
+            Role::Author,
+            lookup(&[
+                ("GIT_AUTHOR_NAME", "CI Bot"),
+                ("GIT_AUTHOR_EMAIL", "ci@example.com"),
+            ]),
+            lookup(&[("user.name", "Jane"), ("user.email", "jane@example.com")]),
+        )
+        .unwrap();
+        assert_eq!(identity.to_string(), "CI Bot <ci@example.com>");
+    }
+
+    #[test]
+    fn test_role_config_before_user_config() {
+        let identity = resolve_with(
+            Role::Committer,
+            lookup(&[("GIT_AUTHOR_NAME", "Ignored")]),
+            lookup(&[
+                ("committer.name", "Release Bot"),
+                ("user.name", "Jane"),
+                ("user.email", "jane@example.com"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(identity.to_string(), "Release Bot <jane@example.com>");
+    }
+
+    #[test]
+    fn test_email_env_fallback_and_missing() {
+        let identity = resolve_with(
+            Role::Author,
+            lookup(&[("EMAIL", "me@example.com")]),
+            lookup(&[("user.name", "Me")]),
+        )
+        .unwrap();
+        assert_eq!(identity.email, "me@example.com");
+
+        let error = resolve_with(Role::Author, lookup(&[]), lookup(&[])).unwrap_err();
+        assert!(error.to_string().contains("GIT_AUTHOR_NAME"));
+        assert!(error.to_string().contains("--author"));
+    }
+}
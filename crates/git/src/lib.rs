@@ -1,8 +1,22 @@
+mod history;
+mod identity;
+mod patch;
+
 use anyhow::{Context, anyhow};
 use async_trait::async_trait;
-use git_gen_core::GitRepository;
+use git_gen_core::diff::render_patches;
+use git_gen_core::version::Version;
+use git_gen_core::{
+    ChangeSet, CommitInfo, CommitOptions, FilePatch, Footer, GitRepository, MergeKind, MergeState,
+};
+use identity::Role;
+use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+
+/// File in the git directory holding the message of the last failed commit
+const SAVED_MESSAGE_FILE: &str = "GIT_GEN_MSG";
 
 /// Implementation of GitRepository using system git command
 pub struct GitRepositoryImpl {
@@ -29,92 +43,504 @@ impl GitRepositoryImpl {
             repo_path: path.as_ref().to_path_buf(),
         }
     }
-}
 
-#[async_trait]
-impl GitRepository for GitRepositoryImpl {
-    async fn get_staged_diff(&self) -> anyhow::Result<String> {
+    /// Run a git command in the repository on the blocking thread pool
+    async fn git(&self, args: &[&str]) -> anyhow::Result<Output> {
         let repo_path = self.repo_path.clone();
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
 
-        // Run in blocking thread pool since Command is synchronous
         tokio::task::spawn_blocking(move || {
-            // Get staged diff using git diff --cached
-            let output = Command::new("git")
-                .args(["diff", "--cached"])
+            git_command()
+                .args(&args)
                 .current_dir(&repo_path)
                 .output()
-                .context("Failed to execute git diff. Make sure git is installed and in PATH")?;
+                .with_context(|| {
+                    format!(
+                        "Failed to execute git {}. Make sure git is installed and in PATH",
+                        args.first().map(String::as_str).unwrap_or_default()
+                    )
+                })
+        })
+        .await
+        .map_err(|e| anyhow!("Task join error: {}", e))?
+    }
+
+    /// Resolve a path inside the git directory, e.g. `MERGE_HEAD`
+    async fn git_path(&self, name: &str) -> anyhow::Result<PathBuf> {
+        let output = self.git(&["rev-parse", "--git-path", name]).await?;
 
-            // git diff returns exit code 0 even when there are no changes
-            // We need to check if the output is empty
-            let diff_text = String::from_utf8_lossy(&output.stdout).to_string();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Not a git repository: {}", stderr.trim()));
+        }
 
-            if diff_text.trim().is_empty() {
-                return Err(anyhow!("No staged changes found"));
+        // Relative results are relative to the directory git ran in
+        Ok(self
+            .repo_path
+            .join(String::from_utf8_lossy(&output.stdout).trim()))
+    }
+
+    /// Get the staged diff in re-applicable form, with extra `git diff` options
+    async fn staged_patch_text(&self, extra_args: &[&str]) -> anyhow::Result<String> {
+        // Explicit prefixes and --binary keep the output re-applicable whatever the user config
+        let mut args = vec![
+            "diff",
+            "--cached",
+            "--binary",
+            "--no-color",
+            "--no-ext-diff",
+            "--find-renames",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+        ];
+        args.extend_from_slice(extra_args);
+        let output = self.git(&args).await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git diff failed: {}", stderr));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Size in bytes of an object, `None` if it cannot be read
+    async fn object_size(&self, id: &str) -> anyhow::Result<Option<u64>> {
+        let output = self.git(&["cat-file", "-s", id]).await?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().parse().ok())
+    }
+
+    /// Fill in blob sizes of binary files and commit lists of submodules
+    async fn describe_changes(&self, changes: &mut ChangeSet) -> anyhow::Result<()> {
+        for change in changes.files.iter_mut().filter(|c| c.binary) {
+            if let Some(id) = &change.old_id {
+                change.old_size = self.object_size(id).await?;
             }
+            if let Some(id) = &change.new_id {
+                change.new_size = self.object_size(id).await?;
+            }
+        }
 
-            // Check for error output
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(anyhow!("Git diff failed: {}", stderr));
+        if changes.files.iter().any(|c| c.is_submodule()) {
+            let output = self
+                .git(&[
+                    "diff",
+                    "--cached",
+                    "--submodule=log",
+                    "--no-color",
+                    "--no-ext-diff",
+                ])
+                .await?;
+            // Missing submodule checkouts only cost the commit lists
+            if output.status.success() {
+                let text = String::from_utf8_lossy(&output.stdout);
+                for (path, log) in patch::parse_submodule_log(&text) {
+                    if let Some(change) = changes.files.iter_mut().find(|c| c.new_path == path) {
+                        change.submodule_log = log;
+                    }
+                }
             }
+        }
+        Ok(())
+    }
+
+    /// Run `git log` with [`history::LOG_FORMAT`] and parse the commits
+    async fn log_commits(&self, args: &[&str]) -> anyhow::Result<Vec<CommitInfo>> {
+        let mut log_args = vec!["log", history::LOG_FORMAT];
+        log_args.extend_from_slice(args);
+        let output = self.git(&log_args).await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git log failed: {}", stderr));
+        }
+
+        Ok(history::parse_log(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Run a git command with `input` written to its stdin
+    async fn git_with_input(&self, args: &[&str], input: &str) -> anyhow::Result<Output> {
+        let repo_path = self.repo_path.clone();
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let input = input.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let mut child = git_command()
+                .args(&args)
+                .current_dir(&repo_path)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .with_context(|| {
+                    format!(
+                        "Failed to execute git {}. Make sure git is installed and in PATH",
+                        args.first().map(String::as_str).unwrap_or_default()
+                    )
+                })?;
 
-            Ok(diff_text)
+            child
+                .stdin
+                .take()
+                .context("Failed to open git stdin")?
+                .write_all(input.as_bytes())
+                .context("Failed to write to git stdin")?;
+
+            child
+                .wait_with_output()
+                .context("Failed to wait for git to finish")
         })
         .await
         .map_err(|e| anyhow!("Task join error: {}", e))?
     }
+}
+
+#[async_trait]
+impl GitRepository for GitRepositoryImpl {
+    async fn get_staged_diff(&self) -> anyhow::Result<String> {
+        let changes = self.get_staged_changes().await?;
+        if changes.is_empty() {
+            return Err(anyhow!("No staged changes found"));
+        }
+        Ok(changes.render_for_prompt())
+    }
+
+    async fn get_staged_files(&self) -> anyhow::Result<Vec<String>> {
+        // -z avoids quoting of unusual file names
+        let output = self.git(&["diff", "--cached", "--name-only", "-z"]).await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git diff failed: {}", stderr));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    async fn get_staged_patches(&self) -> anyhow::Result<Vec<FilePatch>> {
+        let diff = self.staged_patch_text(&[]).await?;
+        Ok(patch::parse_patches(&diff))
+    }
+
+    async fn get_staged_changes(&self) -> anyhow::Result<ChangeSet> {
+        let diff = self.staged_patch_text(&[]).await?;
+        let mut changes = patch::parse_changes(&diff);
+        self.describe_changes(&mut changes).await?;
+        Ok(changes)
+    }
+
+    async fn get_staged_function_patches(&self) -> anyhow::Result<Vec<FilePatch>> {
+        let diff = self.staged_patch_text(&["--function-context"]).await?;
+        Ok(patch::parse_patches(&diff))
+    }
+
+    async fn get_staged_file(&self, path: &str) -> anyhow::Result<Option<String>> {
+        let output = self.git(&["show", &format!(":{}", path)]).await?;
+
+        // Deleted and unmerged paths have no single index entry
+        if !output.status.success() || output.stdout.contains(&0) {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+    }
+
+    async fn reset_index(&self) -> anyhow::Result<()> {
+        let head = self.git(&["rev-parse", "--verify", "-q", "HEAD"]).await?;
+
+        // An unborn branch has no HEAD to reset to, so empty the index instead
+        let output = if head.status.success() {
+            self.git(&["reset", "-q"]).await?
+        } else {
+            self.git(&["read-tree", "--empty"]).await?
+        };
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git reset failed: {}", stderr));
+        }
+        Ok(())
+    }
+
+    async fn stage_patches(&self, patches: &[FilePatch]) -> anyhow::Result<()> {
+        if patches.is_empty() {
+            return Ok(());
+        }
+
+        let output = self
+            .git_with_input(
+                &["apply", "--cached", "--whitespace=nowarn", "-"],
+                &render_patches(patches),
+            )
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git apply failed: {}", stderr));
+        }
+        Ok(())
+    }
+
+    async fn merge_state(&self) -> anyhow::Result<Option<MergeState>> {
+        if let Ok(merge_head) = fs::read_to_string(self.git_path("MERGE_HEAD").await?) {
+            let message = fs::read_to_string(self.git_path("MERGE_MSG").await?).unwrap_or_default();
+            // Octopus merges list several heads; the first one is the main incoming branch
+            let head = merge_head.lines().next().unwrap_or_default().trim();
+            let commits = self.log(&format!("HEAD..{}", head)).await?;
+
+            return Ok(Some(MergeState {
+                kind: MergeKind::Merge,
+                incoming: history::incoming_from_merge_msg(&message),
+                commits,
+                conflicts: history::conflicts_from_merge_msg(&message),
+            }));
+        }
+
+        if let Ok(message) = fs::read_to_string(self.git_path("SQUASH_MSG").await?) {
+            let hashes = history::squashed_hashes(&message);
+            let mut commits = Vec::new();
+            if !hashes.is_empty() {
+                let mut args = vec!["--no-walk=unsorted"];
+                args.extend(hashes.iter().map(String::as_str));
+                commits = self.log_commits(&args).await?;
+                commits.reverse();
+            }
+
+            return Ok(Some(MergeState {
+                kind: MergeKind::Squash,
+                incoming: None,
+                commits,
+                conflicts: Vec::new(),
+            }));
+        }
+
+        Ok(None)
+    }
+
+    async fn log(&self, range: &str) -> anyhow::Result<Vec<CommitInfo>> {
+        self.log_commits(&["--reverse", range, "--"]).await
+    }
+
+    async fn diff_range(&self, range: &str) -> anyhow::Result<String> {
+        let output = self
+            .git(&["diff", "--no-color", "--no-ext-diff", range, "--"])
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git diff failed: {}", stderr));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    async fn show_range(&self, range: &str) -> anyhow::Result<String> {
+        let output = self
+            .git(&[
+                "log",
+                "--reverse",
+                "--patch",
+                "--function-context",
+                "--no-color",
+                "--no-ext-diff",
+                range,
+                "--",
+            ])
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git log failed: {}", stderr));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    async fn soft_reset(&self, rev: &str) -> anyhow::Result<()> {
+        let output = self.git(&["reset", "--soft", "-q", rev]).await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git reset failed: {}", stderr));
+        }
+        Ok(())
+    }
+
+    async fn latest_tag(&self) -> anyhow::Result<Option<String>> {
+        let output = self.git(&["tag", "--merged", "HEAD"]).await?;
+
+        // Fails on an unborn branch, which has no tags either
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        // Tags like `nightly` are skipped; git's version sort would put
+        // `v1.2.3-rc.1` after `v1.2.3`, so compare by semver precedence instead
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .map(str::trim)
+            .filter_map(|tag| Version::parse(tag).map(|version| (version, tag)))
+            .max_by(|(a, _), (b, _)| a.cmp_precedence(b))
+            .map(|(_, tag)| tag.to_string()))
+    }
+
+    async fn create_tag(&self, name: &str, message: &str) -> anyhow::Result<()> {
+        let output = self
+            .git_with_input(&["tag", "-a", name, "-F", "-"], message)
+            .await?;
 
-    async fn commit(&self, message: &str) -> anyhow::Result<()> {
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git tag failed: {}", stderr));
+        }
+        Ok(())
+    }
+
+    async fn root_dir(&self) -> anyhow::Result<PathBuf> {
+        let output = self.git(&["rev-parse", "--show-toplevel"]).await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Not a git repository: {}", stderr.trim()));
+        }
+
+        Ok(PathBuf::from(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ))
+    }
+
+    async fn create_branch(&self, name: &str) -> anyhow::Result<()> {
+        let output = self.git(&["switch", "-c", name]).await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git switch failed: {}", stderr));
+        }
+        Ok(())
+    }
+
+    async fn current_branch(&self) -> anyhow::Result<Option<String>> {
+        // symbolic-ref also works on an unborn branch, unlike rev-parse
+        let output = self.git(&["symbolic-ref", "--short", "-q", "HEAD"]).await?;
+
+        // Exit code 1 means HEAD is detached
+        if output.status.code() == Some(1) {
+            return Ok(None);
+        }
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git symbolic-ref failed: {}", stderr));
+        }
+
+        let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(branch).filter(|b| !b.is_empty()))
+    }
+
+    async fn committer_identity(&self) -> anyhow::Result<String> {
+        let repo_path = self.repo_path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            identity::resolve(&repo_path, Role::Committer).map(|identity| identity.to_string())
+        })
+        .await
+        .map_err(|e| anyhow!("Task join error: {}", e))?
+    }
+
+    async fn recent_authors(&self, limit: usize) -> anyhow::Result<Vec<String>> {
+        // %aN/%aE apply .mailmap so aliases collapse into one entry
+        let output = self
+            .git(&["log", "-n", "500", "--format=%aN <%aE>"])
+            .await?;
+
+        // An unborn branch has no history to pick from
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+
+        let mut authors: Vec<String> = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if authors.len() >= limit {
+                break;
+            }
+            if !line.is_empty() && !authors.iter().any(|a| a == line) {
+                authors.push(line.to_string());
+            }
+        }
+        Ok(authors)
+    }
+
+    async fn add_trailers(&self, message: &str, trailers: &[Footer]) -> anyhow::Result<String> {
+        if trailers.is_empty() {
+            return Ok(message.to_string());
+        }
+
+        let trailer_args: Vec<String> = trailers
+            .iter()
+            .map(|t| format!("{}: {}", t.token, t.value))
+            .collect();
+        let mut args = vec!["interpret-trailers", "--if-exists", "addIfDifferent"];
+        for trailer in &trailer_args {
+            args.push("--trailer");
+            args.push(trailer);
+        }
+
+        let output = self.git_with_input(&args, message).await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Git interpret-trailers failed: {}", stderr));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string())
+    }
+
+    async fn commit(&self, message: &str, options: &CommitOptions) -> anyhow::Result<()> {
         let repo_path = self.repo_path.clone();
         let message = message.to_string();
+        let allow_empty = options.allow_empty;
+        let has_author_override = options.author.is_some();
+        let mut commit_args = vec!["commit".to_string(), "-m".to_string(), message];
+        commit_args.extend(commit_option_args(options));
 
         // Run in blocking thread pool since Command is synchronous
         tokio::task::spawn_blocking(move || {
             // First check if there are staged changes
             // git diff --cached --quiet returns exit code 0 if no changes, 1 if there are changes
-            let diff_output = Command::new("git")
+            let diff_output = git_command()
                 .args(["diff", "--cached", "--quiet"])
                 .current_dir(&repo_path)
                 .output()
                 .context("Failed to execute git diff. Make sure git is installed and in PATH")?;
 
             // Exit code 0 means no staged changes
-            if diff_output.status.code() == Some(0) {
+            if diff_output.status.code() == Some(0) && !allow_empty {
                 return Err(anyhow!("No staged changes to commit"));
             }
 
-            // Check git user config before committing
-            let name_output = Command::new("git")
-                .args(["config", "user.name"])
-                .current_dir(&repo_path)
-                .output()
-                .context("Failed to check git user.name")?;
-
-            if !name_output.status.success() || name_output.stdout.is_empty() {
-                return Err(anyhow!("Git user.name is not configured. Please run: git config user.name \"Your Name\""));
-            }
-
-            let email_output = Command::new("git")
-                .args(["config", "user.email"])
-                .current_dir(&repo_path)
-                .output()
-                .context("Failed to check git user.email")?;
-
-            if !email_output.status.success() || email_output.stdout.is_empty() {
-                return Err(anyhow!("Git user.email is not configured. Please run: git config user.email \"your.email@example.com\""));
+            // Resolve identities like git does; --author replaces the author identity
+            if !has_author_override {
+                identity::resolve(&repo_path, Role::Author)?;
             }
+            identity::resolve(&repo_path, Role::Committer)?;
 
             // Execute git commit
-            let output = Command::new("git")
-                .args(["commit", "-m", &message])
+            let output = git_command()
+                .args(&commit_args)
                 .current_dir(&repo_path)
                 .output()
                 .context("Failed to execute git commit. Make sure git is installed and in PATH")?;
 
             if !output.status.success() {
                 let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(anyhow!("Git commit failed: {}", stderr));
+                return Err(commit_failure(&stderr));
             }
 
             Ok(())
@@ -122,16 +548,233 @@ impl GitRepository for GitRepositoryImpl {
         .await
         .map_err(|e| anyhow!("Task join error: {}", e))?
     }
+
+    async fn save_message(&self, message: &str) -> anyhow::Result<()> {
+        let path = self.git_path(SAVED_MESSAGE_FILE).await?;
+        fs::write(&path, message).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    async fn saved_message(&self) -> anyhow::Result<Option<String>> {
+        let path = self.git_path(SAVED_MESSAGE_FILE).await?;
+        match fs::read_to_string(&path) {
+            Ok(message) if !message.trim().is_empty() => Ok(Some(message.trim_end().to_string())),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    async fn clear_saved_message(&self) -> anyhow::Result<()> {
+        let path = self.git_path(SAVED_MESSAGE_FILE).await?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A `git` command; tests ignore the developer's global and system config
+fn git_command() -> Command {
+    let mut command = Command::new("git");
+    if cfg!(test) {
+        command
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1");
+    }
+    command
+}
+
+/// Translate commit options into `git commit` arguments
+fn commit_option_args(options: &CommitOptions) -> Vec<String> {
+    let mut args = Vec::new();
+
+    // Leaving `sign` unset lets git apply commit.gpgsign itself
+    match (options.sign, &options.signing_key) {
+        (Some(false), _) => args.push("--no-gpg-sign".to_string()),
+        (_, Some(key)) => args.push(format!("--gpg-sign={}", key)),
+        (Some(true), None) => args.push("--gpg-sign".to_string()),
+        (None, None) => {}
+    }
+    if options.no_verify {
+        args.push("--no-verify".to_string());
+    }
+    if let Some(author) = &options.author {
+        args.push(format!("--author={}", author));
+    }
+    if let Some(date) = &options.date {
+        args.push(format!("--date={}", date));
+    }
+    if options.allow_empty {
+        args.push("--allow-empty".to_string());
+    }
+    if let Some(cleanup) = &options.cleanup {
+        args.push(format!("--cleanup={}", cleanup));
+    }
+    args.extend(options.extra_args.iter().cloned());
+
+    args
+}
+
+/// Turn git commit stderr into an error, explaining signing failures
+fn commit_failure(stderr: &str) -> anyhow::Error {
+    let lower = stderr.to_lowercase();
+
+    if lower.contains("inappropriate ioctl for device") || lower.contains("pinentry") {
+        return anyhow!(
+            "Signing failed because gpg could not ask for your passphrase. Run `export GPG_TTY=$(tty)` or configure a graphical pinentry, then retry.\n{}",
+            stderr.trim()
+        );
+    }
+    if lower.contains("failed to sign") || lower.contains("signing failed") {
+        return anyhow!(
+            "Signing the commit failed. Check user.signingkey, gpg.format and that your GPG/SSH agent is running, or pass --no-gpg-sign.\n{}",
+            stderr.trim()
+        );
+    }
+
+    anyhow!("Git commit failed: {}", stderr)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn run(dir: &Path, args: &[&str]) {
+        let output = git_command()
+            .args(["-c", "user.name=Dev", "-c", "user.email=dev@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+    }
+
     #[tokio::test]
     async fn test_git_repository_creation() {
-        let _repo = GitRepositoryImpl::new();
-        // Just test that it can be created
-        assert!(true);
+        let repo = GitRepositoryImpl::new();
+        assert_eq!(repo.repo_path, PathBuf::from("."));
+
+        let repo = GitRepositoryImpl::with_path("/tmp/repo");
+        assert_eq!(repo.repo_path, PathBuf::from("/tmp/repo"));
+    }
+
+    #[test]
+    fn test_commit_option_args() {
+        let options = CommitOptions {
+            sign: Some(true),
+            signing_key: Some("ABC123".to_string()),
+            no_verify: true,
+            author: Some("Jane <jane@example.com>".to_string()),
+            cleanup: Some("verbatim".to_string()),
+            extra_args: vec!["--no-post-rewrite".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            commit_option_args(&options),
+            vec![
+                "--gpg-sign=ABC123",
+                "--no-verify",
+                "--author=Jane <jane@example.com>",
+                "--cleanup=verbatim",
+                "--no-post-rewrite",
+            ]
+        );
+
+        let options = CommitOptions {
+            sign: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(commit_option_args(&options), vec!["--no-gpg-sign"]);
+        assert!(commit_option_args(&CommitOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_commit_failure_signing() {
+        let error = commit_failure(
+            "error: gpg failed to sign the data\nfatal: failed to write commit object",
+        );
+        assert!(error.to_string().starts_with("Signing the commit failed"));
+
+        let error = commit_failure("error: hook declined");
+        assert!(error.to_string().starts_with("Git commit failed"));
+    }
+
+    #[tokio::test]
+    async fn test_add_trailers() {
+        let root = tempfile::tempdir().unwrap();
+        let repo = GitRepositoryImpl::with_path(root.path());
+        let trailers = vec![
+            Footer {
+                token: "Signed-off-by".to_string(),
+                value: "Dev <dev@example.com>".to_string(),
+            },
+            Footer {
+                token: "Refs".to_string(),
+                value: "PROJ-1".to_string(),
+            },
+        ];
+
+        let message = repo
+            .add_trailers("feat: add login\n\nRefs: PROJ-1", &trailers)
+            .await
+            .unwrap();
+        assert_eq!(
+            message,
+            "feat: add login\n\nRefs: PROJ-1\nSigned-off-by: Dev <dev@example.com>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latest_tag_skips_non_semver() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let repo = GitRepositoryImpl::with_path(root);
+        run(root, &["init", "-q"]);
+        assert_eq!(repo.latest_tag().await.unwrap(), None);
+
+        run(root, &["commit", "-q", "--allow-empty", "-m", "init"]);
+        for tag in ["v1.2.0", "v1.10.0-rc.1", "v1.9.0", "nightly"] {
+            run(root, &["tag", tag]);
+        }
+        assert_eq!(
+            repo.latest_tag().await.unwrap().as_deref(),
+            Some("v1.10.0-rc.1")
+        );
+
+        run(root, &["tag", "v1.10.0"]);
+        assert_eq!(repo.latest_tag().await.unwrap().as_deref(), Some("v1.10.0"));
+    }
+
+    #[tokio::test]
+    async fn test_stage_patches_by_hunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let lines: Vec<String> = (1..=20).map(|n| format!("line {}", n)).collect();
+        std::fs::write(root.join("file.txt"), lines.join("\n") + "\n").unwrap();
+        run(root, &["init", "-q"]);
+        run(root, &["add", "file.txt"]);
+        run(root, &["commit", "-q", "-m", "init"]);
+
+        let mut changed = lines.clone();
+        changed[1] = "first change".to_string();
+        changed[18] = "second change".to_string();
+        std::fs::write(root.join("file.txt"), changed.join("\n") + "\n").unwrap();
+        run(root, &["add", "file.txt"]);
+
+        let repo = GitRepositoryImpl::with_path(root);
+        let patches = repo.get_staged_patches().await.unwrap();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].hunks.len(), 2);
+
+        repo.reset_index().await.unwrap();
+        repo.stage_patches(&[patches[0].with_hunks(&[1])])
+            .await
+            .unwrap();
+        let diff = repo.get_staged_diff().await.unwrap();
+
+        assert!(diff.contains("+second change"));
+        assert!(!diff.contains("+first change"));
     }
 }
@@ -0,0 +1,452 @@
+use git_gen_core::{ChangeKind, ChangeSet, DiffStats, FileChange, FilePatch, Hunk};
+
+/// Parse `git diff` output into per-file patches
+///
+/// Expects the default `a/` and `b/` prefixes. Binary patch data stays in the
+/// file header since it has no hunks. Lines are split on `\n` only so CRLF
+/// content keeps its `\r` and re-rendered hunks still apply to the index.
+pub fn parse_patches(diff: &str) -> Vec<FilePatch> {
+    let mut patches: Vec<FilePatch> = Vec::new();
+
+    for line in diff
+        .split_inclusive('\n')
+        .map(|line| line.strip_suffix('\n').unwrap_or(line))
+    {
+        if line.starts_with("diff --git ") {
+            patches.push(FilePatch {
+                path: path_from_diff_line(line),
+                header: vec![line.to_string()],
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(patch) = patches.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            patch.hunks.push(Hunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+        } else if let Some(hunk) = patch.hunks.last_mut() {
+            hunk.lines.push(line.to_string());
+        } else {
+            // The new path is authoritative; the `diff --git` line is ambiguous with spaces
+            if let Some(path) = header_path(line, "+++ ", "b/")
+                .or_else(|| header_path(line, "rename to ", ""))
+                .or_else(|| header_path(line, "copy to ", ""))
+            {
+                patch.path = path;
+            }
+            patch.header.push(line.to_string());
+        }
+    }
+
+    patches
+}
+
+/// Parse `git diff` output into a change set
+pub fn parse_changes(diff: &str) -> ChangeSet {
+    ChangeSet {
+        files: parse_patches(diff).into_iter().map(file_change).collect(),
+    }
+}
+
+/// Classify a patch from its extended header lines
+fn file_change(patch: FilePatch) -> FileChange {
+    let mut kind = ChangeKind::Modified;
+    let mut old_path = None;
+    let mut old_mode = None;
+    let mut new_mode = None;
+    let mut old_id = None;
+    let mut new_id = None;
+    let mut binary = false;
+
+    for line in &patch.header {
+        if let Some(mode) = line.strip_prefix("new file mode ") {
+            kind = ChangeKind::Added;
+            new_mode = Some(mode.to_string());
+        } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+            kind = ChangeKind::Deleted;
+            old_mode = Some(mode.to_string());
+        } else if let Some(path) = header_path(line, "rename from ", "") {
+            kind = ChangeKind::Renamed;
+            old_path = Some(path);
+        } else if let Some(path) = header_path(line, "copy from ", "") {
+            kind = ChangeKind::Copied;
+            old_path = Some(path);
+        } else if let Some(mode) = line.strip_prefix("old mode ") {
+            old_mode = Some(mode.to_string());
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            new_mode = Some(mode.to_string());
+        } else if let Some(index) = line.strip_prefix("index ") {
+            // `index <old>..<new>[ <mode>]`, with the mode only when it is unchanged
+            let (ids, mode) = index.split_once(' ').unwrap_or((index, ""));
+            if let Some((old, new)) = ids.split_once("..") {
+                old_id = object_id(old);
+                new_id = object_id(new);
+            }
+            if !mode.is_empty() {
+                old_mode.get_or_insert_with(|| mode.to_string());
+                new_mode.get_or_insert_with(|| mode.to_string());
+            }
+        } else if let Some(path) = header_path(line, "--- ", "a/") {
+            old_path.get_or_insert(path);
+        } else if line.starts_with("GIT binary patch") || line.starts_with("Binary files ") {
+            binary = true;
+        }
+    }
+    if kind == ChangeKind::Modified && old_mode.is_some() && old_mode != new_mode {
+        kind = ChangeKind::ModeChanged;
+    }
+
+    let mut stats = DiffStats::default();
+    for line in patch.hunks.iter().flat_map(|h| &h.lines) {
+        if line.starts_with('+') {
+            stats.additions += 1;
+        } else if line.starts_with('-') {
+            stats.deletions += 1;
+        }
+    }
+
+    FileChange {
+        kind,
+        old_path: old_path.unwrap_or_else(|| patch.path.clone()),
+        new_path: patch.path.clone(),
+        old_mode,
+        new_mode,
+        old_id,
+        new_id,
+        binary,
+        old_size: None,
+        new_size: None,
+        submodule_log: Vec::new(),
+        stats,
+        patch,
+    }
+}
+
+/// An object id, or `None` for the all-zero id of a missing side
+fn object_id(id: &str) -> Option<String> {
+    (!id.is_empty() && !id.chars().all(|c| c == '0')).then(|| id.to_string())
+}
+
+/// Parse the commit lists of `git diff --submodule=log` by submodule path
+///
+/// Headers look like `Submodule <path> <old>..<new>:`, optionally followed by
+/// a note such as `(rewind)`, with `  > subject` or `  < subject` lines below.
+pub fn parse_submodule_log(diff: &str) -> Vec<(String, Vec<String>)> {
+    let mut logs: Vec<(String, Vec<String>)> = Vec::new();
+    // Log lines directly follow their header; anything else ends the list
+    let mut in_log = false;
+
+    for line in diff.lines() {
+        if let Some(header) = line.strip_prefix("Submodule ") {
+            let header = header.trim_end_matches(':');
+            let header = match header.rfind(" (") {
+                Some(index) if header.ends_with(')') => &header[..index],
+                _ => header,
+            };
+            in_log = false;
+            if let Some((path, _range)) = header.rsplit_once(' ') {
+                logs.push((path.to_string(), Vec::new()));
+                in_log = true;
+            }
+        } else if in_log
+            && let Some(entry) = line
+                .strip_prefix("  ")
+                .filter(|l| l.starts_with("> ") || l.starts_with("< "))
+            && let Some((_, log)) = logs.last_mut()
+        {
+            log.push(entry.to_string());
+        } else {
+            in_log = false;
+        }
+    }
+
+    logs
+}
+
+/// Best-effort path from `diff --git a/<old> b/<new>`
+fn path_from_diff_line(line: &str) -> String {
+    let rest = line.trim_start_matches("diff --git ");
+    if rest.ends_with('"')
+        && let Some(index) = rest.rfind(" \"b/")
+    {
+        let path = unquote(&rest[index + 1..]);
+        return path.strip_prefix("b/").unwrap_or(&path).to_string();
+    }
+    match rest.rfind(" b/") {
+        Some(index) => rest[index + 3..].to_string(),
+        None => rest.to_string(),
+    }
+}
+
+/// Path of a header line such as `+++ b/<path>`, which git may quote
+fn header_path(line: &str, prefix: &str, side: &str) -> Option<String> {
+    let path = unquote(line.strip_prefix(prefix)?);
+    path.strip_prefix(side).map(str::to_string)
+}
+
+/// Undo the C-style quoting git applies to paths with special or, unless
+/// `core.quotePath` is off, non-ASCII characters
+fn unquote(path: &str) -> String {
+    let Some(inner) = path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) else {
+        return path.to_string();
+    };
+
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut rest = inner.bytes().peekable();
+    while let Some(byte) = rest.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match rest.next() {
+            Some(b'a') => bytes.push(0x07),
+            Some(b'b') => bytes.push(0x08),
+            Some(b'f') => bytes.push(0x0c),
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'v') => bytes.push(0x0b),
+            // Non-ASCII bytes are written as three octal digits
+            Some(digit @ b'0'..=b'7') => {
+                let mut value = u32::from(digit - b'0');
+                for _ in 0..2 {
+                    if let Some(&next @ b'0'..=b'7') = rest.peek() {
+                        value = value * 8 + u32::from(next - b'0');
+                        rest.next();
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_gen_core::diff::render_patches;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@
+ fn a() {}
++fn b() {}
+ fn c() {}
+ fn d() {}
+@@ -10,2 +11,2 @@ fn e() {
+-    old();
++    new();
+diff --git a/logo.png b/logo.png
+new file mode 100644
+index 0000000..3333333
+GIT binary patch
+literal 4
+LcmZ?wbhEHbG{6A@
+
+literal 0
+HcmV?d00001
+
+diff --git a/old name.txt b/new name.txt
+similarity index 100%
+rename from old name.txt
+rename to new name.txt
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+index 4444444..0000000
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+
+    #[test]
+    fn test_parse_patches() {
+        let patches = parse_patches(DIFF);
+        assert_eq!(patches.len(), 5);
+
+        assert_eq!(patches[0].path, "src/lib.rs");
+        assert_eq!(patches[0].hunks.len(), 2);
+        assert_eq!(
+            patches[0].hunks[1].lines,
+            vec!["-    old();", "+    new();"]
+        );
+        assert!(!patches[0].is_atomic());
+
+        assert_eq!(patches[1].path, "logo.png");
+        assert!(patches[1].hunks.is_empty());
+        assert!(patches[1].is_atomic());
+
+        assert_eq!(patches[2].path, "new name.txt");
+        assert!(patches[2].is_atomic());
+
+        let rendered: String = patches.iter().map(FilePatch::render).collect();
+        assert_eq!(rendered, DIFF);
+    }
+
+    #[test]
+    fn test_crlf_round_trip() {
+        let diff = "\
+diff --git a/win.txt b/win.txt
+index 1111111..2222222 100644
+--- a/win.txt
++++ b/win.txt
+@@ -1,2 +1,2 @@
+-one\r
++uno\r
+ two\r
+";
+        let patches = parse_patches(diff);
+        assert_eq!(patches[0].hunks[0].lines[0], "-one\r");
+        assert_eq!(render_patches(&patches), diff);
+    }
+
+    #[test]
+    fn test_quoted_paths() {
+        let patches = parse_patches(
+            "\
+diff --git \"a/caf\\303\\251.txt\" \"b/caf\\303\\251.txt\"
+new file mode 100644
+index 0000000..1111111
+--- /dev/null
++++ \"b/caf\\303\\251.txt\"
+@@ -0,0 +1 @@
++hi
+diff --git \"a/tab\\there\" \"b/tab\\there\"
+old mode 100644
+new mode 100755
+",
+        );
+        assert_eq!(patches[0].path, "café.txt");
+        assert_eq!(patches[1].path, "tab\there");
+
+        let changes = parse_changes(
+            "\
+diff --git \"a/\\346\\227\\247.md\" \"b/new.md\"
+similarity index 100%
+rename from \"\\346\\227\\247.md\"
+rename to new.md
+",
+        );
+        assert_eq!(changes.files[0].old_path, "旧.md");
+        assert_eq!(changes.files[0].new_path, "new.md");
+    }
+
+    #[test]
+    fn test_parse_changes() {
+        let changes = parse_changes(DIFF);
+        let kinds: Vec<ChangeKind> = changes.files.iter().map(|f| f.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ChangeKind::Modified,
+                ChangeKind::Added,
+                ChangeKind::Renamed,
+                ChangeKind::ModeChanged,
+                ChangeKind::Deleted,
+            ]
+        );
+
+        let renamed = &changes.files[2];
+        assert_eq!(renamed.old_path, "old name.txt");
+        assert_eq!(renamed.new_path, "new name.txt");
+        assert!(changes.files[1].binary);
+        assert_eq!(changes.files[3].new_mode.as_deref(), Some("100755"));
+        assert_eq!(changes.files[4].old_path, "gone.txt");
+        assert_eq!(
+            changes.stats(),
+            DiffStats {
+                additions: 2,
+                deletions: 2
+            }
+        );
+        assert_eq!(changes.render(), DIFF);
+        assert_eq!(changes.filter(|f| f.binary).paths(), vec!["logo.png"]);
+    }
+
+    #[test]
+    fn test_summaries() {
+        let mut changes = parse_changes(DIFF);
+        assert_eq!(changes.files[0].summary(), None);
+        assert_eq!(changes.files[0].old_mode.as_deref(), Some("100644"));
+        assert_eq!(changes.files[1].new_id.as_deref(), Some("3333333"));
+        assert_eq!(changes.files[1].old_id, None);
+
+        changes.files[1].new_size = Some(12 * 1024);
+        assert_eq!(
+            changes.files[1].render_for_prompt(),
+            "binary image logo.png added (12KB)\n"
+        );
+        assert_eq!(
+            changes.files[2].render_for_prompt(),
+            "renamed old name.txt → new name.txt\n"
+        );
+        assert_eq!(
+            changes.files[3].summary().as_deref(),
+            Some("made run.sh executable")
+        );
+
+        let mut changes = parse_changes(
+            "\
+diff --git a/vendor/lib b/vendor/lib
+index 1234567abc..89abcdef01 160000
+--- a/vendor/lib
++++ b/vendor/lib
+@@ -1 +1 @@
+-Subproject commit 1234567abc
++Subproject commit 89abcdef01
+",
+        );
+        let submodule = &mut changes.files[0];
+        assert!(submodule.is_submodule());
+        assert_eq!(submodule.kind, ChangeKind::Modified);
+        assert_eq!(
+            submodule.render_for_prompt(),
+            "submodule vendor/lib updated 1234567..89abcde\n"
+        );
+
+        submodule.submodule_log = vec!["> Fix parser".to_string(), "> Add lexer".to_string()];
+        assert_eq!(
+            submodule.render_for_prompt(),
+            "submodule vendor/lib bumped 2 commits:\n  > Fix parser\n  > Add lexer\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_submodule_log() {
+        let logs = parse_submodule_log(
+            "\
+Submodule vendor/my lib 1234567..89abcde:
+  > Fix parser
+  > Add lexer
+Submodule old 89abcde...1234567 (rewind):
+  < Revert change
+diff --git a/README.md b/README.md
+",
+        );
+        assert_eq!(
+            logs,
+            vec![
+                (
+                    "vendor/my lib".to_string(),
+                    vec!["> Fix parser".to_string(), "> Add lexer".to_string()]
+                ),
+                ("old".to_string(), vec!["< Revert change".to_string()]),
+            ]
+        );
+    }
+}
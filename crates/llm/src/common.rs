@@ -1,3 +1,7 @@
+use git_gen_core::RequestPreview;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
 /// Maximum length of diff to send to LLM
 pub const MAX_DIFF_LENGTH: usize = 114514;
 
@@ -13,25 +17,73 @@ pub fn truncate_diff(diff: String) -> String {
     }
 }
 
-/// Validate and extract commit message from LLM response
-pub fn validate_commit_message(message: String, provider_name: &str) -> anyhow::Result<String> {
+/// Rough token count; English text and code average about four characters per token
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Where a request goes and what it contains, for [`preview_request`]
+pub struct RequestParts<'a, B: Serialize> {
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: &'a B,
+    pub redacted: Vec<String>,
+}
+
+/// Describe a request for a dry run
+pub fn preview_request<B: Serialize>(
+    parts: RequestParts<'_, B>,
+    system_prompt: &str,
+    original_message: &str,
+    sent_message: &str,
+) -> RequestPreview {
+    RequestPreview {
+        provider: parts.provider.to_string(),
+        model: parts.model.to_string(),
+        url: parts.url,
+        headers: parts.headers,
+        // Round trip through text so floats print as they are sent, e.g. 0.7 rather than 0.699999988
+        body: serde_json::to_string(parts.body)
+            .and_then(|text| serde_json::from_str(&text))
+            .unwrap_or_default(),
+        original_length: original_message.chars().count(),
+        sent_length: sent_message.chars().count(),
+        truncated: original_message != sent_message,
+        redacted: parts.redacted,
+        estimated_tokens: estimate_tokens(system_prompt) + estimate_tokens(sent_message),
+    }
+}
+
+/// Build the system prompt with any extra instructions appended
+pub fn build_system_prompt(instructions: &[String]) -> String {
+    let mut prompt = crate::prompt::SYSTEM_PROMPT.to_string();
+    if !instructions.is_empty() {
+        prompt.push_str("\n## Additional Instructions\n\n");
+        for instruction in instructions {
+            prompt.push_str(&format!("- {}\n", instruction));
+        }
+    }
+    prompt
+}
+
+/// Validate and extract the text of an LLM response
+pub fn validate_response(message: String, provider_name: &str) -> anyhow::Result<String> {
     let message = message.trim().to_string();
 
     if message.is_empty() {
-        return Err(anyhow::anyhow!(
-            "Empty commit message from {} API",
-            provider_name
-        ));
+        return Err(anyhow::anyhow!("Empty response from {} API", provider_name));
     }
 
     Ok(message)
 }
 
 /// Handle API error response
-pub async fn handle_api_error(
+pub async fn handle_api_error<T>(
     response: reqwest::Response,
     provider_name: &str,
-) -> anyhow::Result<String> {
+) -> anyhow::Result<T> {
     let status = response.status();
     let error_text = response
         .text()
@@ -45,3 +97,31 @@ pub async fn handle_api_error(
         error_text
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_request() {
+        let body = serde_json::json!({ "model": "gpt" });
+        let preview = preview_request(
+            RequestParts {
+                provider: "OpenAI",
+                model: "gpt",
+                url: "https://example.com".to_string(),
+                headers: BTreeMap::new(),
+                body: &body,
+                redacted: vec!["Authorization header".to_string()],
+            },
+            "12345678",
+            "abcdefgh",
+            "abcd",
+        );
+        assert!(preview.truncated);
+        assert_eq!(preview.original_length, 8);
+        assert_eq!(preview.sent_length, 4);
+        assert_eq!(preview.estimated_tokens, 3);
+        assert_eq!(preview.body, body);
+    }
+}
@@ -3,43 +3,98 @@ use super::types::{
     Content, GenerateContentRequest, GenerateContentResponse, GenerationConfig, Part,
     SystemInstruction,
 };
-use crate::common::{TEMPERATURE, handle_api_error, truncate_diff, validate_commit_message};
+use crate::common::{
+    RequestParts, TEMPERATURE, build_system_prompt, handle_api_error, preview_request,
+    truncate_diff, validate_response,
+};
 use anyhow::anyhow;
+use git_gen_core::{GenerationRequest, RequestPreview, TokenUsage};
+use std::collections::BTreeMap;
 
 const GEMINI_API_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
 
 pub async fn generate_commit_message(
     config: &GeminiConfig,
-    diff: String,
+    request: GenerationRequest,
     client: &reqwest::Client,
-) -> anyhow::Result<String> {
-    // Truncate diff if too long
-    let diff = truncate_diff(diff);
+) -> anyhow::Result<(String, TokenUsage)> {
+    complete(
+        config,
+        &build_system_prompt(&request.instructions),
+        request.diff,
+        client,
+    )
+    .await
+}
+
+pub fn preview_commit_message(config: &GeminiConfig, request: GenerationRequest) -> RequestPreview {
+    preview(
+        config,
+        &build_system_prompt(&request.instructions),
+        request.diff,
+    )
+}
+
+/// Describe the request [`complete`] would send
+pub fn preview(config: &GeminiConfig, system_prompt: &str, user_message: String) -> RequestPreview {
+    let body = content_request(system_prompt, truncate_diff(user_message.clone()));
+    let headers = BTreeMap::from([("Content-Type".to_string(), "application/json".to_string())]);
+    let sent_message = &body.contents[0].parts[0].text;
+
+    preview_request(
+        RequestParts {
+            provider: "Gemini",
+            model: &config.model,
+            url: endpoint(config, "<redacted>"),
+            headers,
+            body: &body,
+            redacted: vec!["API key in the key query parameter".to_string()],
+        },
+        system_prompt,
+        &user_message,
+        sent_message,
+    )
+}
+
+fn endpoint(config: &GeminiConfig, api_key: &str) -> String {
+    format!(
+        "{}/models/{}:generateContent?key={}",
+        GEMINI_API_BASE_URL, config.model, api_key
+    )
+}
 
-    let request = GenerateContentRequest {
+/// Build the request body for an already truncated user message
+fn content_request(system_prompt: &str, user_message: String) -> GenerateContentRequest {
+    GenerateContentRequest {
         contents: vec![Content {
-            parts: vec![Part { text: diff }],
+            parts: vec![Part { text: user_message }],
             role: Some("user".to_string()),
         }],
         system_instruction: Some(SystemInstruction {
             parts: vec![Part {
-                text: crate::prompt::SYSTEM_PROMPT.to_string(),
+                text: system_prompt.to_string(),
             }],
         }),
         generation_config: Some(GenerationConfig {
             temperature: Some(TEMPERATURE),
         }),
-    };
+    }
+}
 
-    let url = format!(
-        "{}/models/{}:generateContent?key={}",
-        GEMINI_API_BASE_URL, config.model, config.api_key
-    );
+pub async fn complete(
+    config: &GeminiConfig,
+    system_prompt: &str,
+    user_message: String,
+    client: &reqwest::Client,
+) -> anyhow::Result<(String, TokenUsage)> {
+    // Truncate the diff-bearing message if too long
+    let body = content_request(system_prompt, truncate_diff(user_message));
+    let url = endpoint(config, &config.api_key);
 
     let response = client
         .post(&url)
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json(&body)
         .send()
         .await
         .map_err(|e| anyhow!("Failed to send request to Gemini API: {}", e))?;
@@ -60,5 +115,10 @@ pub async fn generate_commit_message(
         .map(|part| part.text.clone())
         .ok_or_else(|| anyhow!("No message in Gemini API response"))?;
 
-    validate_commit_message(message, "Gemini")
+    let usage = gemini_response
+        .usage_metadata
+        .map(|usage| usage.token_usage())
+        .unwrap_or_default();
+
+    Ok((validate_response(message, "Gemini")?, usage))
 }
@@ -1,3 +1,4 @@
+use git_gen_core::TokenUsage;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
@@ -34,6 +35,29 @@ pub struct GenerationConfig {
 #[derive(Deserialize)]
 pub struct GenerateContentResponse {
     pub candidates: Vec<Candidate>,
+    #[serde(default, rename = "usageMetadata")]
+    pub usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageMetadata {
+    #[serde(default)]
+    pub prompt_token_count: u64,
+    #[serde(default)]
+    pub candidates_token_count: u64,
+    /// Tokens spent on thinking, billed as output but not part of the candidates
+    #[serde(default)]
+    pub thoughts_token_count: u64,
+}
+
+impl UsageMetadata {
+    pub fn token_usage(&self) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_token_count,
+            completion_tokens: self.candidates_token_count + self.thoughts_token_count,
+        }
+    }
 }
 
 #[derive(Deserialize)]
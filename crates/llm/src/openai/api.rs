@@ -1,38 +1,93 @@
 use super::config::OpenAIConfig;
 use super::types::{ChatMessage, ChatRequest, ChatResponse};
-use crate::common::{TEMPERATURE, handle_api_error, truncate_diff, validate_commit_message};
+use crate::common::{
+    RequestParts, TEMPERATURE, build_system_prompt, handle_api_error, preview_request,
+    truncate_diff, validate_response,
+};
 use anyhow::anyhow;
+use git_gen_core::{GenerationRequest, RequestPreview, TokenUsage};
+use std::collections::BTreeMap;
 
 const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
 
 pub async fn generate_commit_message(
     config: &OpenAIConfig,
-    diff: String,
+    request: GenerationRequest,
     client: &reqwest::Client,
-) -> anyhow::Result<String> {
-    // Truncate diff if too long
-    let diff = truncate_diff(diff);
+) -> anyhow::Result<(String, TokenUsage)> {
+    complete(
+        config,
+        &build_system_prompt(&request.instructions),
+        request.diff,
+        client,
+    )
+    .await
+}
+
+pub fn preview_commit_message(config: &OpenAIConfig, request: GenerationRequest) -> RequestPreview {
+    preview(
+        config,
+        &build_system_prompt(&request.instructions),
+        request.diff,
+    )
+}
+
+/// Describe the request [`complete`] would send
+pub fn preview(config: &OpenAIConfig, system_prompt: &str, user_message: String) -> RequestPreview {
+    let body = chat_request(config, system_prompt, truncate_diff(user_message.clone()));
+    let headers = BTreeMap::from([
+        ("Authorization".to_string(), "Bearer <redacted>".to_string()),
+        ("Content-Type".to_string(), "application/json".to_string()),
+    ]);
+    let sent_message = &body.messages[1].content;
+
+    preview_request(
+        RequestParts {
+            provider: "OpenAI",
+            model: &config.model,
+            url: OPENAI_API_URL.to_string(),
+            headers,
+            body: &body,
+            redacted: vec!["API key in the Authorization header".to_string()],
+        },
+        system_prompt,
+        &user_message,
+        sent_message,
+    )
+}
 
-    let request = ChatRequest {
+/// Build the chat request for an already truncated user message
+fn chat_request(config: &OpenAIConfig, system_prompt: &str, user_message: String) -> ChatRequest {
+    ChatRequest {
         model: config.model.clone(),
         messages: vec![
             ChatMessage {
                 role: "system".to_string(),
-                content: crate::prompt::SYSTEM_PROMPT.to_string(),
+                content: system_prompt.to_string(),
             },
             ChatMessage {
                 role: "user".to_string(),
-                content: diff,
+                content: user_message,
             },
         ],
         temperature: TEMPERATURE,
-    };
+    }
+}
+
+pub async fn complete(
+    config: &OpenAIConfig,
+    system_prompt: &str,
+    user_message: String,
+    client: &reqwest::Client,
+) -> anyhow::Result<(String, TokenUsage)> {
+    // Truncate the diff-bearing message if too long
+    let body = chat_request(config, system_prompt, truncate_diff(user_message));
 
     let response = client
         .post(OPENAI_API_URL)
         .header("Authorization", format!("Bearer {}", config.api_key))
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json(&body)
         .send()
         .await
         .map_err(|e| anyhow!("Failed to send request to OpenAI API: {}", e))?;
@@ -52,5 +107,10 @@ pub async fn generate_commit_message(
         .map(|choice| choice.message.content.clone())
         .ok_or_else(|| anyhow!("No message in OpenAI API response"))?;
 
-    validate_commit_message(message, "OpenAI")
+    let usage = chat_response
+        .usage
+        .map(|usage| usage.token_usage())
+        .unwrap_or_default();
+
+    Ok((validate_response(message, "OpenAI")?, usage))
 }
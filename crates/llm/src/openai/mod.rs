@@ -1,6 +1,7 @@
 use async_trait::async_trait;
-use git_gen_core::LlmGenerater;
+use git_gen_core::{GenerationRequest, LlmGenerater, RequestPreview, TokenUsage};
 use std::env;
+use std::sync::Mutex;
 
 mod api;
 mod config;
@@ -12,6 +13,7 @@ pub use config::OpenAIConfig;
 pub struct OpenAILlmGenerater {
     config: OpenAIConfig,
     client: reqwest::Client,
+    usage: Mutex<TokenUsage>,
 }
 
 impl OpenAILlmGenerater {
@@ -20,6 +22,7 @@ impl OpenAILlmGenerater {
         Self {
             config: OpenAIConfig::new(api_key, model),
             client: reqwest::Client::new(),
+            usage: Mutex::new(TokenUsage::default()),
         }
     }
 
@@ -32,12 +35,38 @@ impl OpenAILlmGenerater {
 
         Ok(Self::with_config(api_key, model))
     }
+
+    fn add_usage(&self, usage: TokenUsage) {
+        *self.usage.lock().unwrap() += usage;
+    }
 }
 
 #[async_trait]
 impl LlmGenerater for OpenAILlmGenerater {
-    async fn generate_commit_message(&self, diff: String) -> anyhow::Result<String> {
-        api::generate_commit_message(&self.config, diff, &self.client).await
+    async fn generate_commit_message(&self, request: GenerationRequest) -> anyhow::Result<String> {
+        let (message, usage) =
+            api::generate_commit_message(&self.config, request, &self.client).await?;
+        self.add_usage(usage);
+        Ok(message)
+    }
+
+    async fn complete(&self, system_prompt: &str, user_message: String) -> anyhow::Result<String> {
+        let (message, usage) =
+            api::complete(&self.config, system_prompt, user_message, &self.client).await?;
+        self.add_usage(usage);
+        Ok(message)
+    }
+
+    fn preview_commit_message(&self, request: GenerationRequest) -> RequestPreview {
+        api::preview_commit_message(&self.config, request)
+    }
+
+    fn preview(&self, system_prompt: &str, user_message: String) -> RequestPreview {
+        api::preview(&self.config, system_prompt, user_message)
+    }
+
+    fn usage(&self) -> TokenUsage {
+        *self.usage.lock().unwrap()
     }
 }
 
@@ -50,4 +79,19 @@ mod tests {
         assert!(!prompt.is_empty());
         assert!(prompt.contains("commit message"));
     }
+
+    #[test]
+    fn test_parse_usage() {
+        let response: super::types::ChatResponse = serde_json::from_str(
+            r#"{
+                "choices": [{"message": {"role": "assistant", "content": "fix: typo"}}],
+                "usage": {"prompt_tokens": 120, "completion_tokens": 40, "total_tokens": 160,
+                    "completion_tokens_details": {"reasoning_tokens": 32}}
+            }"#,
+        )
+        .unwrap();
+        let usage = response.usage.unwrap().token_usage();
+        assert_eq!(usage.prompt_tokens, 120);
+        assert_eq!(usage.completion_tokens, 40);
+    }
 }
@@ -1,3 +1,4 @@
+use git_gen_core::TokenUsage;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
@@ -16,6 +17,26 @@ pub struct ChatMessage {
 #[derive(Deserialize)]
 pub struct ChatResponse {
     pub choices: Vec<Choice>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+}
+
+impl Usage {
+    /// Reasoning tokens are already part of `completion_tokens`
+    pub fn token_usage(&self) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+        }
+    }
 }
 
 #[derive(Deserialize)]